@@ -1,5 +1,11 @@
+use glam::IVec2;
+
 use crate::{
-    gameplay::{CommandList, InputCommand, Move, MoveType},
+    gameplay::{
+        AttackData, CancelProperties, CollisionBoxes, CommandList, FrameData, HitKind, Hitboxes,
+        Hurtboxes, InputCommand, KnockdownKind, Move, MoveData, MoveType, ParryData,
+        ProjectileData, Rect, StrikeData, ThrowData,
+    },
     graphics::{AnimationData, AnimationKey, Animations},
     static_data,
 };
@@ -11,69 +17,819 @@ pub struct CharacterDefinition {
     pub max_hp: usize,
     pub forward_walk_speed: isize,
     pub backward_walk_speed: isize,
+    pub forward_dash_speed: isize,
+    pub forward_dash_frames: usize,
+    pub backward_dash_speed: isize,
+    pub backward_dash_frames: usize,
+    pub jump_forward_speed: isize,
+    pub jump_backward_speed: isize,
+    /// Upwards speed when leaving the ground.
+    pub jump_velocity: isize,
+    /// Added to vertical velocity each frame while airborne.
+    pub gravity: isize,
+    /// Frames after landing from a jump before the character can act again.
+    pub landing_frames: usize,
+    /// Super meter is spent in stocks, with up to `super_stocks` stocks of
+    /// `super_stock_size` meter each.
+    pub super_stock_size: usize,
+    pub super_stocks: usize,
+    pub max_boost_meter: usize,
+    /// Boost meter spent on each boost.
+    pub boost_cost: usize,
+    /// Boost meter regained each frame.
+    pub boost_regen: usize,
+    pub boost_speed: isize,
+    pub boost_frames: usize,
+    /// Frames spent lying down after landing from a soft knockdown juggle.
+    pub soft_knockdown_frames: usize,
+    /// Frames spent lying down after landing from a hard knockdown juggle.
+    pub hard_knockdown_frames: usize,
+    /// Frames spent getting up after a knockdown, invincible throughout.
+    pub wakeup_frames: usize,
+    pub hurtboxes: Hurtboxes,
+    pub crouching_hurtboxes: Hurtboxes,
+    pub push_box: &'static [CollisionBoxes],
+    /// Usually shorter than `push_box`, so characters can jump over each other.
+    pub airborne_push_box: &'static [CollisionBoxes],
 
     pub command_list: &'static CommandList,
     pub graphics: &'static CharacterGraphicsData,
 }
 
+/// Every playable character, for looking them up by name.
+pub static CHARACTERS: &[&CharacterDefinition] = &[&DEFAULT_CHARACTER];
+
+pub fn find_character(name: &str) -> Option<&'static CharacterDefinition> {
+    CHARACTERS
+        .iter()
+        .copied()
+        .find(|character| character.name == name)
+}
+
 pub static DEFAULT_CHARACTER: CharacterDefinition = CharacterDefinition {
     name: "Default Character",
     max_hp: 1000,
     forward_walk_speed: 15,
     backward_walk_speed: 12,
+    forward_dash_speed: 60,
+    forward_dash_frames: 16,
+    backward_dash_speed: 50,
+    backward_dash_frames: 20,
+    jump_forward_speed: 30,
+    jump_backward_speed: 25,
+    jump_velocity: 150,
+    gravity: 8,
+    landing_frames: 3,
+    super_stock_size: 1000,
+    super_stocks: 3,
+    max_boost_meter: 1000,
+    boost_cost: 500,
+    boost_regen: 2,
+    boost_speed: 90,
+    boost_frames: 12,
+    soft_knockdown_frames: 10,
+    hard_knockdown_frames: 40,
+    wakeup_frames: 20,
+    hurtboxes: Hurtboxes {
+        collision: STANDING_HURTBOXES,
+    },
+    crouching_hurtboxes: Hurtboxes {
+        collision: CROUCHING_HURTBOXES,
+    },
+    push_box: &[CollisionBoxes {
+        data: &[Rect::new(-200, -1600, 200, 0)],
+    }],
+    airborne_push_box: &[CollisionBoxes {
+        data: &[Rect::new(-200, -1400, 200, -600)],
+    }],
     command_list: &DEFAULT_CHARACTER_COMMAND_LIST,
     graphics: &static_data::CHARACTER_GRAPHICS_DATA,
 };
 
+// Boxes are relative to the character's feet while facing right,
+// in world units where 1000 units is a metre.
+const STANDING_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(-250, -1700, 250, 0)],
+}];
+
+const CROUCHING_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(-250, -1100, 250, 0)],
+}];
+const JAB_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(200, -1400, 650, -1200)],
+}];
+const JAB_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[
+        Rect::new(-250, -1700, 250, 0),
+        Rect::new(200, -1400, 550, -1200),
+    ],
+}];
+
+const STRAIGHT_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(200, -1300, 800, -1050)],
+}];
+const STRAIGHT_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[
+        Rect::new(-250, -1700, 250, 0),
+        Rect::new(200, -1300, 700, -1050),
+    ],
+}];
+
+const HEAVY_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(250, -1400, 950, -900)],
+}];
+const HEAVY_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[
+        Rect::new(-250, -1700, 250, 0),
+        Rect::new(200, -1400, 850, -900),
+    ],
+}];
+
+const CROUCH_JAB_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(150, -700, 600, -450)],
+}];
+const CROUCH_KICK_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(150, -300, 800, 0)],
+}];
+const SWEEP_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(200, -250, 1000, 0)],
+}];
+const CROUCHING_ATTACK_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(-250, -1100, 250, 0), Rect::new(150, -500, 700, 0)],
+}];
+
+const JUMP_LIGHT_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(100, -900, 550, -500)],
+}];
+const JUMP_HEAVY_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(0, -700, 700, -100)],
+}];
+const JUMP_ATTACK_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[
+        Rect::new(-250, -1500, 250, -200),
+        Rect::new(100, -800, 500, -300),
+    ],
+}];
+
+const UPPER_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(100, -2100, 550, -900)],
+}];
+const UPPER_HURTBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[
+        Rect::new(-250, -1700, 250, 0),
+        Rect::new(100, -2000, 450, -1000),
+    ],
+}];
+
+// Fireball boxes are relative to the fireball itself.
+const FIREBALL_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(-200, -1300, 200, -900)],
+}];
+
+const THROW_HITBOXES: &[CollisionBoxes] = &[CollisionBoxes {
+    data: &[Rect::new(100, -1500, 650, -500)],
+}];
+
+/// Startup, active and recovery segments of a basic attack, where the limb
+/// described by `hurtboxes` stays vulnerable from the first active frame
+/// until the attack has recovered. Only the active frames can be canceled.
+const fn attack_frames(
+    startup: usize,
+    active: usize,
+    recovery: usize,
+    attack_data: AttackData,
+    hitboxes: &'static [CollisionBoxes],
+    hurtboxes: &'static [CollisionBoxes],
+) -> [FrameData; 3] {
+    [
+        FrameData {
+            duration: startup,
+            hitboxes: None,
+            hurtboxes: None,
+            blocking_volume: &[],
+            parry: None,
+            projectile: None,
+            cancellable: false,
+        },
+        FrameData {
+            duration: active,
+            hitboxes: Some(Hitboxes {
+                attack_data,
+                collision: hitboxes,
+            }),
+            hurtboxes: Some(Hurtboxes {
+                collision: hurtboxes,
+            }),
+            blocking_volume: &[],
+            parry: None,
+            projectile: None,
+            cancellable: true,
+        },
+        FrameData {
+            duration: recovery,
+            hitboxes: None,
+            hurtboxes: Some(Hurtboxes {
+                collision: hurtboxes,
+            }),
+            blocking_volume: &[],
+            parry: None,
+            projectile: None,
+            cancellable: false,
+        },
+    ]
+}
+
+/// Startup and recovery segments of a move which throws a projectile
+/// as the recovery begins.
+const fn projectile_frames(
+    startup: usize,
+    recovery: usize,
+    projectile: ProjectileData,
+) -> [FrameData; 2] {
+    [
+        FrameData {
+            duration: startup,
+            hitboxes: None,
+            hurtboxes: None,
+            blocking_volume: &[],
+            parry: None,
+            projectile: None,
+            cancellable: false,
+        },
+        FrameData {
+            duration: recovery,
+            hitboxes: None,
+            hurtboxes: None,
+            blocking_volume: &[],
+            parry: None,
+            projectile: Some(projectile),
+            cancellable: false,
+        },
+    ]
+}
+
+/// A single hit fireball travelling at `speed`.
+const fn fireball(speed: isize) -> ProjectileData {
+    ProjectileData {
+        spawn_offset: IVec2::new(500, 0),
+        speed,
+        lifetime: 180,
+        hit_count: 1,
+        hit_interval: 0,
+        strike: StrikeData {
+            damage: 60,
+            hit_stun: 18,
+            block_stun: 14,
+            block_hitstop_frames: 6,
+            hit_hitstop_frames: 8,
+            cancel_properties: CancelProperties::NoCancel,
+            hit_kind: HitKind::Medium,
+            knockback: IVec2::new(30, 0),
+            juggle_cost: 1,
+            ground_bounce: false,
+            wall_bounce: false,
+            knockdown: KnockdownKind::Soft,
+            hit_meter_gain: 30,
+            block_meter_gain: 15,
+            defender_meter_gain: 20,
+        },
+        collision: FIREBALL_HITBOXES,
+    }
+}
+
+/// Startup, parry window and whiff recovery segments of a parry. Parrying
+/// a strike skips the rest of the move.
+const fn parry_frames(
+    startup: usize,
+    window: usize,
+    recovery: usize,
+    parry: ParryData,
+) -> [FrameData; 3] {
+    [
+        FrameData {
+            duration: startup,
+            hitboxes: None,
+            hurtboxes: None,
+            blocking_volume: &[],
+            parry: None,
+            projectile: None,
+            cancellable: false,
+        },
+        FrameData {
+            duration: window,
+            hitboxes: None,
+            hurtboxes: None,
+            blocking_volume: &[],
+            parry: Some(parry),
+            projectile: None,
+            cancellable: false,
+        },
+        FrameData {
+            duration: recovery,
+            hitboxes: None,
+            hurtboxes: None,
+            blocking_volume: &[],
+            parry: None,
+            projectile: None,
+            cancellable: false,
+        },
+    ]
+}
+
 static DEFAULT_CHARACTER_COMMAND_LIST: CommandList = CommandList {
     moves: &[
         Move {
             command: InputCommand::from_notation("5a"),
-            move_type: MoveType::Normal,
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    5,
+                    3,
+                    8,
+                    AttackData::Strike(StrikeData {
+                        damage: 30,
+                        hit_stun: 14,
+                        block_stun: 10,
+                        block_hitstop_frames: 6,
+                        hit_hitstop_frames: 8,
+                        cancel_properties: CancelProperties::ChainCancel,
+                        hit_kind: HitKind::Medium,
+                        knockback: IVec2::new(30, 0),
+                        juggle_cost: 1,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 15,
+                        block_meter_gain: 7,
+                        defender_meter_gain: 20,
+                    }),
+                    JAB_HITBOXES,
+                    JAB_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("5b"),
-            move_type: MoveType::Normal,
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    8,
+                    4,
+                    14,
+                    AttackData::Strike(StrikeData {
+                        damage: 50,
+                        hit_stun: 17,
+                        block_stun: 13,
+                        block_hitstop_frames: 8,
+                        hit_hitstop_frames: 10,
+                        cancel_properties: CancelProperties::ChainCancel,
+                        hit_kind: HitKind::Medium,
+                        knockback: IVec2::new(40, 0),
+                        juggle_cost: 1,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 25,
+                        block_meter_gain: 12,
+                        defender_meter_gain: 20,
+                    }),
+                    STRAIGHT_HITBOXES,
+                    STRAIGHT_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("5c"),
-            move_type: MoveType::Normal,
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    12,
+                    4,
+                    22,
+                    AttackData::Strike(StrikeData {
+                        damage: 80,
+                        hit_stun: 21,
+                        block_stun: 16,
+                        block_hitstop_frames: 10,
+                        hit_hitstop_frames: 12,
+                        cancel_properties: CancelProperties::SpecialCancel,
+                        hit_kind: HitKind::Medium,
+                        knockback: IVec2::new(55, 0),
+                        juggle_cost: 2,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 40,
+                        block_meter_gain: 20,
+                        defender_meter_gain: 20,
+                    }),
+                    HEAVY_HITBOXES,
+                    HEAVY_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("2a"),
-            move_type: MoveType::Normal,
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    5,
+                    2,
+                    8,
+                    AttackData::Strike(StrikeData {
+                        damage: 25,
+                        hit_stun: 13,
+                        block_stun: 9,
+                        block_hitstop_frames: 6,
+                        hit_hitstop_frames: 8,
+                        cancel_properties: CancelProperties::ChainCancel,
+                        hit_kind: HitKind::Low,
+                        knockback: IVec2::new(28, 0),
+                        juggle_cost: 1,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 12,
+                        block_meter_gain: 6,
+                        defender_meter_gain: 20,
+                    }),
+                    CROUCH_JAB_HITBOXES,
+                    CROUCHING_ATTACK_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("2b"),
-            move_type: MoveType::Normal,
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    7,
+                    3,
+                    12,
+                    AttackData::Strike(StrikeData {
+                        damage: 45,
+                        hit_stun: 16,
+                        block_stun: 12,
+                        block_hitstop_frames: 8,
+                        hit_hitstop_frames: 10,
+                        cancel_properties: CancelProperties::ChainCancel,
+                        hit_kind: HitKind::Low,
+                        knockback: IVec2::new(38, 0),
+                        juggle_cost: 1,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 22,
+                        block_meter_gain: 11,
+                        defender_meter_gain: 20,
+                    }),
+                    CROUCH_KICK_HITBOXES,
+                    CROUCHING_ATTACK_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("2c"),
-            move_type: MoveType::Normal,
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    10,
+                    3,
+                    24,
+                    AttackData::Strike(StrikeData {
+                        damage: 70,
+                        hit_stun: 20,
+                        block_stun: 14,
+                        block_hitstop_frames: 10,
+                        hit_hitstop_frames: 12,
+                        cancel_properties: CancelProperties::NoCancel,
+                        hit_kind: HitKind::Low,
+                        knockback: IVec2::new(30, -40),
+                        juggle_cost: 2,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Hard,
+                        hit_meter_gain: 35,
+                        block_meter_gain: 17,
+                        defender_meter_gain: 20,
+                    }),
+                    SWEEP_HITBOXES,
+                    CROUCHING_ATTACK_HURTBOXES,
+                ),
+            },
+        },
+        Move {
+            command: InputCommand::from_notation("j.5a"),
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    5,
+                    6,
+                    6,
+                    AttackData::Strike(StrikeData {
+                        damage: 35,
+                        hit_stun: 14,
+                        block_stun: 10,
+                        block_hitstop_frames: 6,
+                        hit_hitstop_frames: 8,
+                        cancel_properties: CancelProperties::ChainCancel,
+                        hit_kind: HitKind::High,
+                        knockback: IVec2::new(20, 0),
+                        juggle_cost: 1,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 17,
+                        block_meter_gain: 8,
+                        defender_meter_gain: 20,
+                    }),
+                    JUMP_LIGHT_HITBOXES,
+                    JUMP_ATTACK_HURTBOXES,
+                ),
+            },
+        },
+        Move {
+            command: InputCommand::from_notation("j.5b"),
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    7,
+                    5,
+                    10,
+                    AttackData::Strike(StrikeData {
+                        damage: 55,
+                        hit_stun: 17,
+                        block_stun: 13,
+                        block_hitstop_frames: 8,
+                        hit_hitstop_frames: 10,
+                        cancel_properties: CancelProperties::ChainCancel,
+                        hit_kind: HitKind::High,
+                        knockback: IVec2::new(25, 0),
+                        juggle_cost: 1,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 27,
+                        block_meter_gain: 13,
+                        defender_meter_gain: 20,
+                    }),
+                    JUMP_LIGHT_HITBOXES,
+                    JUMP_ATTACK_HURTBOXES,
+                ),
+            },
+        },
+        Move {
+            command: InputCommand::from_notation("j.5c"),
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    9,
+                    5,
+                    14,
+                    AttackData::Strike(StrikeData {
+                        damage: 75,
+                        hit_stun: 20,
+                        block_stun: 15,
+                        block_hitstop_frames: 10,
+                        hit_hitstop_frames: 12,
+                        cancel_properties: CancelProperties::NoCancel,
+                        hit_kind: HitKind::High,
+                        knockback: IVec2::new(30, 0),
+                        juggle_cost: 2,
+                        ground_bounce: true,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 37,
+                        block_meter_gain: 18,
+                        defender_meter_gain: 20,
+                    }),
+                    JUMP_HEAVY_HITBOXES,
+                    JUMP_ATTACK_HURTBOXES,
+                ),
+            },
+        },
+        Move {
+            command: InputCommand::from_notation("5as"), // Throw
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    3,
+                    2,
+                    20,
+                    AttackData::Throw(ThrowData {
+                        damage: 120,
+                        duration: 40,
+                        tech_window: 10,
+                        hold_offset: 600,
+                        release_offset: 1500,
+                        knockdown_frames: 40,
+                    }),
+                    THROW_HITBOXES,
+                    STANDING_HURTBOXES,
+                ),
+            },
+        },
+        Move {
+            command: InputCommand::from_notation("4as"), // Back Throw
+            data: MoveData {
+                move_type: MoveType::Normal,
+                whiff_meter_gain: 5,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    3,
+                    2,
+                    20,
+                    AttackData::Throw(ThrowData {
+                        damage: 120,
+                        duration: 40,
+                        tech_window: 10,
+                        hold_offset: 600,
+                        release_offset: -1500,
+                        knockdown_frames: 40,
+                    }),
+                    THROW_HITBOXES,
+                    STANDING_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("4s"), // Parry
-            move_type: MoveType::Special,
+            data: MoveData {
+                move_type: MoveType::Special,
+                whiff_meter_gain: 20,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &parry_frames(
+                    2,
+                    8,
+                    20,
+                    ParryData {
+                        hitstop_frames: 12,
+                        advantage_frames: 10,
+                        meter_gain: 100,
+                    },
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("5s"), // Upper
-            move_type: MoveType::Special,
+            data: MoveData {
+                move_type: MoveType::Special,
+                whiff_meter_gain: 20,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    6,
+                    4,
+                    25,
+                    AttackData::Strike(StrikeData {
+                        damage: 90,
+                        hit_stun: 24,
+                        block_stun: 12,
+                        block_hitstop_frames: 10,
+                        hit_hitstop_frames: 12,
+                        cancel_properties: CancelProperties::SuperCancel,
+                        hit_kind: HitKind::Medium,
+                        knockback: IVec2::new(15, -100),
+                        juggle_cost: 2,
+                        ground_bounce: false,
+                        wall_bounce: false,
+                        knockdown: KnockdownKind::Soft,
+                        hit_meter_gain: 45,
+                        block_meter_gain: 22,
+                        defender_meter_gain: 20,
+                    }),
+                    UPPER_HITBOXES,
+                    UPPER_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("6s"), // Strong Upper
-            move_type: MoveType::Special,
+            data: MoveData {
+                move_type: MoveType::Special,
+                whiff_meter_gain: 20,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &attack_frames(
+                    10,
+                    5,
+                    30,
+                    AttackData::Strike(StrikeData {
+                        damage: 120,
+                        hit_stun: 28,
+                        block_stun: 14,
+                        block_hitstop_frames: 12,
+                        hit_hitstop_frames: 14,
+                        cancel_properties: CancelProperties::SuperCancel,
+                        hit_kind: HitKind::Medium,
+                        knockback: IVec2::new(20, -130),
+                        juggle_cost: 3,
+                        ground_bounce: false,
+                        wall_bounce: true,
+                        knockdown: KnockdownKind::Hard,
+                        hit_meter_gain: 60,
+                        block_meter_gain: 30,
+                        defender_meter_gain: 20,
+                    }),
+                    UPPER_HITBOXES,
+                    UPPER_HURTBOXES,
+                ),
+            },
+        },
+        Move {
+            command: InputCommand::from_notation("236cs"), // Super Upper
+            data: MoveData {
+                move_type: MoveType::Super,
+                whiff_meter_gain: 0,
+                meter_cost: 1000,
+                super_flash_frames: 40,
+                frame_data: &attack_frames(
+                    4,
+                    6,
+                    40,
+                    AttackData::Strike(StrikeData {
+                        damage: 300,
+                        hit_stun: 30,
+                        block_stun: 16,
+                        block_hitstop_frames: 12,
+                        hit_hitstop_frames: 20,
+                        cancel_properties: CancelProperties::NoCancel,
+                        hit_kind: HitKind::Medium,
+                        knockback: IVec2::new(30, -150),
+                        juggle_cost: 0,
+                        ground_bounce: false,
+                        wall_bounce: true,
+                        knockdown: KnockdownKind::Hard,
+                        hit_meter_gain: 0,
+                        block_meter_gain: 0,
+                        defender_meter_gain: 50,
+                    }),
+                    UPPER_HITBOXES,
+                    UPPER_HURTBOXES,
+                ),
+            },
         },
         Move {
             command: InputCommand::from_notation("1s"), // Slow Fb
-            move_type: MoveType::Special,
+            data: MoveData {
+                move_type: MoveType::Special,
+                whiff_meter_gain: 20,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &projectile_frames(12, 33, fireball(40)),
+            },
         },
         Move {
             command: InputCommand::from_notation("2s"), // Medium Fb
-            move_type: MoveType::Special,
+            data: MoveData {
+                move_type: MoveType::Special,
+                whiff_meter_gain: 20,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &projectile_frames(12, 33, fireball(60)),
+            },
         },
         Move {
             command: InputCommand::from_notation("3s"), // Fast FB
-            move_type: MoveType::Special,
+            data: MoveData {
+                move_type: MoveType::Special,
+                whiff_meter_gain: 20,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &projectile_frames(12, 33, fireball(80)),
+            },
         },
     ],
 };
@@ -0,0 +1,392 @@
+use std::cell::RefCell;
+
+use glam::{Mat4, Quat, Vec3};
+
+use crate::{
+    api::*,
+    db::DEFAULT_CHARACTER,
+    gameplay::{Facing, MatchSettings, MatchState, PlayerGameState, RoundPhase},
+    input,
+    replay::{Playback, Replay},
+    texture,
+};
+
+const KEYFRAME_SPEED: usize = 8;
+const WORLD_UNITS_PER_METRE: f32 = 1000.0;
+
+const PLAYER_1_ID: i32 = 0;
+const PLAYER_2_ID: i32 = 1;
+
+struct GameState {
+    match_state: MatchState,
+    /// The live match so far, for watching back once it's over.
+    replay: Replay,
+    playback: Option<Playback>,
+    keyframe: usize,
+    texture_id: i32,
+    matcap_id: i32,
+    ticker: usize,
+}
+
+impl GameState {
+    /// The match being played back if there is one, otherwise the live match.
+    fn displayed_match(&self) -> &MatchState {
+        self.playback
+            .as_ref()
+            .map_or(&self.match_state, Playback::match_state)
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<GameState> = RefCell::new(GameState {
+        match_state: MatchState::new(
+            &DEFAULT_CHARACTER,
+            &DEFAULT_CHARACTER,
+            MatchSettings::default(),
+        ),
+        replay: Replay::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, 0, MatchSettings::default()),
+        playback: None,
+        keyframe: 0,
+        texture_id: 0,
+        matcap_id: 0,
+        ticker: 0,
+    });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn init() {
+    let text = "Init Fighting Frame";
+    let text2 = "Init Done";
+    let texture = texture::generate_texture();
+    let matcap = texture::generate_matcap_bytes(256);
+    unsafe {
+        console_log(text.as_ptr(), text.len() as i32);
+        STATE.with_borrow_mut(|state| {
+            let seed = random_int_range(0, i32::MAX);
+            set_seed(seed);
+
+            let settings = MatchSettings {
+                frames_per_second: host_fps(),
+                ..MatchSettings::default()
+            };
+            state.match_state = MatchState::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, settings);
+            state.replay = Replay::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, seed, settings);
+
+            state.texture_id = load_texture(
+                texture.as_ptr(),
+                texture::TEXTURE_WIDTH as i32,
+                texture::TEXTURE_HEIGHT as i32,
+                1,
+            );
+            state.matcap_id = load_texture(matcap.as_ptr(), 256, 256, 1);
+
+            for mesh in state.match_state.player_1.character.graphics.meshes {
+                load_static_mesh_indexed(
+                    mesh.vertices.as_ptr() as *const u8,
+                    mesh.vertices.len() as i32,
+                    mesh.indices.as_ptr() as *const u8,
+                    mesh.indices.len() as i32,
+                    6,
+                );
+            }
+        });
+
+        console_log(text2.as_ptr(), text2.len() as i32);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn update() {
+    STATE.with_borrow_mut(|state| {
+        if let Some(playback) = &mut state.playback {
+            let controls = input::poll_playback_controls(PLAYER_1_ID);
+            if controls.toggle_pause && playback.is_finished() {
+                state.playback = None;
+            } else {
+                if controls.toggle_pause {
+                    playback.toggle_pause();
+                }
+                if controls.step {
+                    playback.step();
+                }
+                playback.set_fast_forward(controls.fast_forward);
+                playback.update();
+            }
+        } else if let RoundPhase::MatchOver(_) = state.match_state.phase {
+            // Start watches the match back once it's over.
+            if input::start_pressed(PLAYER_1_ID) {
+                unsafe { set_seed(state.replay.seed) };
+                state.playback = Some(Playback::new(state.replay.clone()));
+            }
+        } else {
+            let (p1_facing, p2_facing) = state.match_state.facings();
+            let p1_inputs = input::poll_player(PLAYER_1_ID, p1_facing);
+            let p2_inputs = input::poll_player(PLAYER_2_ID, p2_facing);
+            state.replay.record(p1_inputs, p2_inputs);
+            state.match_state.update(p1_inputs, p2_inputs);
+        }
+
+        state.keyframe += 1;
+        state.keyframe %= 4 * KEYFRAME_SPEED;
+        state.ticker += 1;
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn render() {
+    let proj = Mat4::perspective_infinite_reverse_rh(71.0_f32.to_radians(), 16.0 / 9.0, 0.1);
+    let pos = Vec3::new(5.0, 1.0, 0.0);
+    let view = Mat4::look_to_rh(pos, Vec3::NEG_X, Vec3::Y);
+
+    // TODO: Clean up how we draw this projection stuff
+    let p_value = STATE.with_borrow(|state| state.ticker) as f32 * 0.0005;
+    let rot = -15f32.to_radians();
+
+    unsafe {
+        push_proj_matrix(&raw const proj as *const u8);
+        push_view_matrix_pos(&raw const view as *const u8, &raw const pos as *const u8);
+
+        STATE.with_borrow(|state| {
+            set_texture(state.texture_id, 0, 0);
+            let keyframe = state.keyframe / KEYFRAME_SPEED;
+            let key_mod = state.keyframe % KEYFRAME_SPEED;
+            let s = key_mod as f32 / KEYFRAME_SPEED as f32;
+
+            let match_state = state.displayed_match();
+            draw_player(&match_state.player_1, keyframe, s, rot);
+
+            set_matcap(state.matcap_id, 1, 3);
+            draw_player(&match_state.player_2, keyframe, s, rot);
+        })
+    };
+}
+
+/// The host's frame rate, or the default if it reports one which would stop
+/// the round timer from working.
+fn host_fps() -> usize {
+    match unsafe { fps() } {
+        fps if fps > 0 => fps as usize,
+        _ => MatchSettings::default().frames_per_second,
+    }
+}
+
+/// Draws a player's meshes, mirrored along the stage when they face left.
+fn draw_player(player: &PlayerGameState, keyframe: usize, s: f32, rot: f32) {
+    let transform = match player.facing {
+        Facing::Right => {
+            Mat4::from_translation(world_position(player)) * Mat4::from_rotation_y(rot)
+        }
+        Facing::Left => Mat4::from_scale_rotation_translation(
+            Vec3::new(1.0, 1.0, -1.0),
+            Quat::from_rotation_y(-rot),
+            world_position(player),
+        ),
+    };
+
+    unsafe {
+        // Mirroring flips the triangles, so their winding order flips too.
+        set_winding_order((player.facing == Facing::Left) as i32);
+
+        for i in 0..player.character.graphics.meshes.len() {
+            let model = transform
+                * player.character.graphics.animations[1]
+                    .blend(keyframe, i, s)
+                    .matrix();
+            push_model_matrix(&raw const model as *const u8);
+            draw_static_mesh_indexed(i as i32);
+        }
+    }
+}
+
+/// Converts a player's gameplay position to world space. The camera looks
+/// down -X, so gameplay x runs along -Z, and gameplay y points downwards.
+fn world_position(player: &PlayerGameState) -> Vec3 {
+    Vec3::new(0.0, -player.pos_y as f32, -player.pos_x as f32) / WORLD_UNITS_PER_METRE
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use crate::{
+        db::DEFAULT_CHARACTER,
+        gameplay::{MatchResult, RoundPhase},
+        mock_host::{self, Button, HostCommand},
+        texture,
+    };
+
+    use super::{KEYFRAME_SPEED, PLAYER_1_ID, STATE, init, render, update, world_position};
+
+    /// Runs the game for a frame, returning the commands it sent the host.
+    fn run_frame() -> Vec<HostCommand> {
+        unsafe {
+            update();
+            render();
+        }
+        mock_host::next_frame();
+        mock_host::take_commands()
+    }
+
+    fn start() -> Vec<HostCommand> {
+        mock_host::reset();
+        unsafe { init() };
+        mock_host::take_commands()
+    }
+
+    fn model_translations(commands: &[HostCommand]) -> Vec<Vec3> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                HostCommand::ModelMatrix(model) => Some(model.w_axis.truncate()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn init_seeds_the_host_and_loads_resources() {
+        let commands = start();
+        let mesh_count = DEFAULT_CHARACTER.graphics.meshes.len();
+
+        assert_eq!(
+            commands.first(),
+            Some(&HostCommand::Log("Init Fighting Frame".to_string()))
+        );
+        assert_eq!(
+            commands.last(),
+            Some(&HostCommand::Log("Init Done".to_string()))
+        );
+        assert!(matches!(commands[1], HostCommand::SetSeed(_)));
+        assert_eq!(
+            commands[2],
+            HostCommand::LoadTexture {
+                id: 0,
+                width: texture::TEXTURE_WIDTH as i32,
+                height: texture::TEXTURE_HEIGHT as i32,
+                has_alpha: 1,
+            }
+        );
+        assert_eq!(
+            commands[3],
+            HostCommand::LoadTexture {
+                id: 1,
+                width: 256,
+                height: 256,
+                has_alpha: 1,
+            }
+        );
+
+        let meshes: Vec<_> = commands
+            .iter()
+            .filter_map(|command| match command {
+                HostCommand::LoadStaticMeshIndexed { id, pipeline, .. } => Some((*id, *pipeline)),
+                _ => None,
+            })
+            .collect();
+        let expected: Vec<_> = (0..mesh_count as i32).map(|id| (id, 6)).collect();
+        assert_eq!(meshes, expected);
+    }
+
+    #[test]
+    fn invalid_frame_rates_fall_back_to_the_default() {
+        for fps in [0, -1, i32::MIN] {
+            mock_host::reset();
+            mock_host::set_fps(fps);
+            unsafe { init() };
+
+            let settings = STATE.with_borrow(|state| state.match_state.settings);
+            assert_eq!(settings.frames_per_second, 60);
+            assert_eq!(settings.round_frames(), Some(99 * 60));
+        }
+    }
+
+    #[test]
+    fn render_draws_each_player_facing_the_other() {
+        start();
+        let commands = run_frame();
+        let mesh_count = DEFAULT_CHARACTER.graphics.meshes.len();
+
+        assert!(matches!(commands[0], HostCommand::ProjMatrix(_)));
+        assert!(matches!(
+            commands[1],
+            HostCommand::ViewMatrix { pos, .. } if pos == Vec3::new(5.0, 1.0, 0.0)
+        ));
+
+        // Player 1 faces right and is drawn as is, player 2 is mirrored.
+        let winding: Vec<_> = commands
+            .iter()
+            .filter_map(|command| match command {
+                HostCommand::SetWindingOrder(clockwise) => Some(*clockwise),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(winding, [0, 1]);
+
+        let draws: Vec<_> = commands
+            .iter()
+            .filter_map(|command| match command {
+                HostCommand::DrawStaticMeshIndexed(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let player_draws: Vec<_> = (0..mesh_count as i32).collect();
+        assert_eq!(draws, [player_draws.clone(), player_draws].concat());
+
+        let textures = commands
+            .iter()
+            .position(|command| matches!(command, HostCommand::SetTexture { id: 0, .. }));
+        let matcap = commands
+            .iter()
+            .position(|command| matches!(command, HostCommand::SetMatcap { id: 1, .. }));
+        assert!(textures < matcap);
+        assert_eq!(model_translations(&commands).len(), 2 * mesh_count);
+    }
+
+    #[test]
+    fn scripted_buttons_move_the_player() {
+        start();
+        let frames = 200;
+        mock_host::hold(PLAYER_1_ID, 0..frames, &[Button::Right]);
+
+        let player_1_position =
+            || STATE.with_borrow(|state| world_position(&state.match_state.player_1));
+        let mut drawn = Vec::new();
+        for _ in 0..frames {
+            let commands = run_frame();
+            drawn.push((player_1_position(), model_translations(&commands)));
+        }
+        assert_eq!(mock_host::frame(), frames);
+
+        // Frames a whole animation cycle apart are drawn in the same pose,
+        // so their meshes are only moved by how far the player walked.
+        let (start_position, start_meshes) = &drawn[100];
+        let (end_position, end_meshes) = &drawn[100 + 2 * 4 * KEYFRAME_SPEED];
+        let walked = end_position - start_position;
+        // Gameplay x runs along -Z, so walking forwards moves player 1 to -Z.
+        assert!(walked.z < 0.0);
+        // Player 1's meshes are drawn first.
+        let mesh_count = DEFAULT_CHARACTER.graphics.meshes.len();
+        for (start, end) in start_meshes.iter().zip(end_meshes).take(mesh_count) {
+            assert!((end - start - walked).length() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn start_watches_the_match_back_once_over() {
+        let HostCommand::SetSeed(seed) = start()[1] else {
+            panic!("init should seed the host");
+        };
+        run_frame();
+        STATE.with_borrow_mut(|state| {
+            state.match_state.phase = RoundPhase::MatchOver(MatchResult::Player1Win)
+        });
+
+        let frame = mock_host::frame();
+        mock_host::hold(PLAYER_1_ID, frame..frame + 1, &[Button::Start]);
+        // Playback reseeds the host, as the match was played with that seed.
+        assert!(run_frame().contains(&HostCommand::SetSeed(seed)));
+
+        run_frame();
+        STATE.with_borrow(|state| assert_eq!(state.playback.as_ref().unwrap().frame(), 1));
+    }
+}
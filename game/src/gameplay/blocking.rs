@@ -0,0 +1,149 @@
+use super::{
+    CharacterState, HitKind, HorizontalInput, MatchState, PlayerGameState, StrikeData,
+    VerticalInput,
+};
+
+/// How close an attacking opponent must be before holding back
+/// puts a character into a guard pose, in world units.
+const PROXIMITY_GUARD_RANGE: isize = 1500;
+
+impl MatchState {
+    /// Puts characters holding back into a guard pose while the opponent is
+    /// close by with a strike that's still to come, so they stop walking away
+    /// and stay ready to block. Throws can't be blocked, so don't count.
+    /// Characters frozen by hitstop or a super flash keep their state.
+    pub(super) fn update_proximity_guard(&mut self) {
+        let p1_guarding = self.player_1.should_guard(&self.player_2);
+        let p2_guarding = self.player_2.should_guard(&self.player_1);
+
+        if self.player_1.freeze_frames == 0 {
+            self.player_1.set_guarding(p1_guarding);
+        }
+        if self.player_2.freeze_frames == 0 {
+            self.player_2.set_guarding(p2_guarding);
+        }
+    }
+}
+
+impl PlayerGameState {
+    /// Whether the strike would be blocked by the direction held right now.
+    /// Lows must be blocked crouching, highs standing and mediums either way.
+    pub(super) fn blocks(&self, strike: &StrikeData) -> bool {
+        if !self.character_state.can_block() || !self.holding_back() {
+            return false;
+        }
+
+        match self.input_buffer.get_last_entry().state.direction.vertical {
+            VerticalInput::Neutral => strike.hit_kind != HitKind::Low,
+            VerticalInput::Down => strike.hit_kind != HitKind::High,
+            VerticalInput::Up => false,
+        }
+    }
+
+    fn holding_back(&self) -> bool {
+        let direction = self.input_buffer.get_last_entry().state.direction;
+        direction.horizontal == HorizontalInput::Backward && direction.vertical != VerticalInput::Up
+    }
+
+    fn should_guard(&self, opponent: &PlayerGameState) -> bool {
+        let threatened = match &opponent.character_state {
+            CharacterState::Attacking { attack, frame, .. } => attack.data.has_strikes_from(*frame),
+            _ => false,
+        };
+
+        threatened
+            && self.holding_back()
+            && self.character_state.can_block()
+            && (self.pos_x - opponent.pos_x).abs() <= PROXIMITY_GUARD_RANGE
+    }
+
+    fn set_guarding(&mut self, guarding: bool) {
+        match (&self.character_state, guarding) {
+            (
+                CharacterState::Idle | CharacterState::Walking { .. } | CharacterState::Crouching,
+                true,
+            ) => self.change_state(CharacterState::Guarding),
+            (CharacterState::Guarding, false) => self.change_state(CharacterState::Idle),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, MatchState,
+        testing::{close_attack, hold, input, jump_in, new_close_fight},
+    };
+
+    /// Plays out the attack with player 2 holding `defence`, returning
+    /// whether they blocked it.
+    fn blocks(mut state: MatchState, defence: &str) -> bool {
+        let max_hp = state.player_2.character.max_hp;
+        let mut blocked = false;
+        for _ in 0..30 {
+            state.update(input("5"), input(defence));
+            blocked |= matches!(state.player_2.character_state, CharacterState::Blockstun(_));
+        }
+
+        let hit = state.player_2.character_stats.health < max_hp;
+        assert!(blocked != hit, "the attack should connect exactly once");
+        blocked
+    }
+
+    #[test]
+    fn mids_are_blocked_standing_or_crouching() {
+        assert!(blocks(close_attack("5c"), "4"));
+        assert!(blocks(close_attack("5c"), "1"));
+        assert!(!blocks(close_attack("5c"), "5"));
+    }
+
+    #[test]
+    fn lows_must_be_blocked_crouching() {
+        assert!(blocks(close_attack("2a"), "1"));
+        assert!(!blocks(close_attack("2a"), "4"));
+    }
+
+    #[test]
+    fn overheads_must_be_blocked_standing() {
+        assert!(blocks(jump_in(), "4"));
+        assert!(!blocks(jump_in(), "1"));
+    }
+
+    #[test]
+    fn holding_back_near_an_attack_guards_instead_of_walking() {
+        let mut state = new_close_fight();
+        state.update(input("5b"), input("4"));
+
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Guarding
+        ));
+        let pos_x = state.player_2.pos_x;
+        hold(&mut state, 3, "5", "4");
+        assert_eq!(state.player_2.pos_x, pos_x);
+    }
+
+    #[test]
+    fn throws_dont_cause_a_guard() {
+        let mut state = new_close_fight();
+        state.update(input("5as"), input("4"));
+
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Walking { backward: true }
+        ));
+    }
+
+    #[test]
+    fn frozen_characters_dont_start_guarding() {
+        let mut state = new_close_fight();
+        state.player_2.freeze_frames = 10;
+        state.update(input("5b"), input("4"));
+
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Idle
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use super::{ButtonInput, CharacterState, PlayerGameState, command_list::COMMAND_BUFFER_WINDOW};
+
+impl PlayerGameState {
+    /// Whether BS was just pressed with enough meter to boost, in a state
+    /// which allows it. Characters in stun are locked out.
+    pub(super) fn boost_input(&self) -> bool {
+        self.character_state.allowed_actions().boost
+            && self.character_stats.boost_meter >= self.character.boost_cost
+            && self
+                .input_buffer
+                .pressed(ButtonInput::BS, COMMAND_BUFFER_WINDOW)
+    }
+
+    /// Refills boost meter a little each frame, other than while boosting.
+    pub(super) fn regenerate_boost(&mut self) {
+        if matches!(self.character_state, CharacterState::Boosting(_)) {
+            return;
+        }
+
+        let meter = &mut self.character_stats.boost_meter;
+        *meter = (*meter + self.character.boost_regen).min(self.character.max_boost_meter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, MatchState,
+        testing::{hold, input, jump_in, new_close_fight, new_fight},
+    };
+
+    fn is_boosting(state: &MatchState) -> bool {
+        matches!(state.player_1.character_state, CharacterState::Boosting(_))
+    }
+
+    #[test]
+    fn one_press_boosts_once_without_attacking() {
+        let mut state = new_fight();
+        let mut boosts = 0;
+        let mut was_boosting = false;
+
+        state.update(input("5bs"), input("5"));
+        for _ in 0..40 {
+            boosts += usize::from(is_boosting(&state) && !was_boosting);
+            was_boosting = is_boosting(&state);
+            assert!(!matches!(
+                state.player_1.character_state,
+                CharacterState::Attacking { .. }
+            ));
+            state.update(input("5bs"), input("5"));
+        }
+
+        assert_eq!(boosts, 1);
+    }
+
+    #[test]
+    fn boosting_spends_meter_which_refills_afterwards() {
+        let mut state = new_fight();
+        let character = state.player_1.character;
+        let full = character.max_boost_meter;
+
+        state.update(input("5bs"), input("5"));
+        assert!(is_boosting(&state));
+        assert_eq!(
+            state.player_1.character_stats.boost_meter,
+            full - character.boost_cost
+        );
+
+        // The meter doesn't refill while boosting.
+        hold(&mut state, character.boost_frames - 1, "5", "5");
+        assert!(is_boosting(&state));
+        assert_eq!(
+            state.player_1.character_stats.boost_meter,
+            full - character.boost_cost
+        );
+
+        hold(&mut state, 11, "5", "5");
+        assert!(!is_boosting(&state));
+        assert_eq!(
+            state.player_1.character_stats.boost_meter,
+            full - character.boost_cost + 10 * character.boost_regen
+        );
+    }
+
+    #[test]
+    fn boosting_needs_enough_meter() {
+        let mut state = new_fight();
+        state.player_1.character_stats.boost_meter = state.player_1.character.boost_cost - 10;
+
+        state.update(input("5bs"), input("5"));
+        assert!(!is_boosting(&state));
+    }
+
+    #[test]
+    fn characters_in_hitstun_cant_boost() {
+        let mut state = new_fight();
+        state.player_1.character_state = CharacterState::Hitstun(20);
+
+        hold(&mut state, 1, "5bs", "5");
+        assert!(!is_boosting(&state));
+    }
+
+    #[test]
+    fn boosting_cancels_grounded_moves_but_not_air_moves() {
+        let mut state = new_close_fight();
+        hold(&mut state, 3, "5c", "5");
+        state.update(input("5bs"), input("5"));
+        assert!(is_boosting(&state));
+
+        let mut state = jump_in();
+        state.update(input("5bs"), input("5"));
+        assert!(!is_boosting(&state));
+    }
+}
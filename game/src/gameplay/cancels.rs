@@ -0,0 +1,159 @@
+use super::{AttackData, CharacterState, Move, PlayerGameState};
+
+impl PlayerGameState {
+    /// Returns the move to cancel into, if the current move has hit or been
+    /// blocked (but not parried), is within a cancel window, and a move its
+    /// strike allows canceling into has been input.
+    pub(super) fn find_cancel(&self) -> Option<&'static Move> {
+        let CharacterState::Attacking {
+            attack,
+            frame,
+            contact: Some(contact),
+        } = &self.character_state
+        else {
+            return None;
+        };
+
+        let (_, frame_data) = attack.data.get_frame_data(*frame)?;
+        if !frame_data.cancellable || contact.parried {
+            return None;
+        }
+
+        let hitboxes = attack.data.frame_data[contact.segment].hitboxes.as_ref()?;
+        let AttackData::Strike(strike) = &hitboxes.attack_data else {
+            return None;
+        };
+
+        self.character.command_list.get_cancel(
+            &self.input_buffer,
+            &self.character_state,
+            attack,
+            &strike.cancel_properties,
+            self.character_stats.super_meter,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, InputCommand, MatchState,
+        testing::{input, new_close_fight, new_fight},
+    };
+
+    /// Presses `first`, then the `next` inputs one per frame from `delay`
+    /// frames later, holding neutral in between.
+    fn then(first: &'static str, delay: usize, next: &[&'static str]) -> Vec<&'static str> {
+        let mut inputs = vec!["5"; delay];
+        inputs[0] = first;
+        inputs.extend(next);
+        inputs
+    }
+
+    fn is_attacking(state: &MatchState, notation: &'static str) -> bool {
+        matches!(
+            &state.player_1.character_state,
+            CharacterState::Attacking { attack, .. }
+                if attack.command == InputCommand::from_notation(notation)
+        )
+    }
+
+    /// Plays each player's inputs one per frame, followed by neutral, and
+    /// returns whether player 1 ever performed the move `notation`.
+    fn performs(
+        mut state: MatchState,
+        player_1: &[&str],
+        player_2: &[&str],
+        notation: &'static str,
+    ) -> bool {
+        (0..60).any(|frame| {
+            state.update(
+                input(player_1.get(frame).unwrap_or(&"5")),
+                input(player_2.get(frame).unwrap_or(&"5")),
+            );
+            is_attacking(&state, notation)
+        })
+    }
+
+    // The default character's 5A hits on frame 5, then both players are
+    // frozen in hitstop for 8 frames.
+
+    #[test]
+    fn normals_chain_into_stronger_normals_on_hit_or_block() {
+        let jab = then("5a", 7, &["5b"]);
+        assert!(performs(new_close_fight(), &jab, &[], "5b"));
+        assert!(performs(new_close_fight(), &jab, &["4"; 30], "5b"));
+    }
+
+    #[test]
+    fn normals_which_whiff_cant_be_canceled() {
+        assert!(!performs(new_fight(), &then("5a", 7, &["5b"]), &[], "5b"));
+    }
+
+    #[test]
+    fn cancels_must_be_input_during_the_cancellable_segment() {
+        // Pressed once the jab's active frames are over.
+        let late = then("5a", 18, &["5b"]);
+        assert!(!performs(new_close_fight(), &late, &[], "5b"));
+    }
+
+    #[test]
+    fn normals_cancel_into_specials_and_specials_into_supers() {
+        let heavy = then("5c", 13, &["5s"]);
+        assert!(performs(new_close_fight(), &heavy, &[], "5s"));
+
+        let mut state = new_close_fight();
+        state.player_1.character_stats.super_meter = state.player_1.max_super_meter();
+        let upper = then("5s", 7, &["2", "3", "6cs"]);
+        assert!(performs(state, &upper, &[], "236cs"));
+    }
+
+    #[test]
+    fn buffered_cancels_come_out_on_the_first_cancellable_frame() {
+        let mut state = new_close_fight();
+        state.update(input("5c"), input("5"));
+        while state.player_1.freeze_frames == 0 {
+            state.update(input("5"), input("5"));
+        }
+
+        // The special is pressed at the start of the hitstop.
+        state.update(input("5s"), input("5"));
+        while state.player_1.freeze_frames > 0 {
+            state.update(input("5"), input("5"));
+        }
+        assert!(is_attacking(&state, "5c"));
+
+        state.update(input("5"), input("5"));
+        assert!(is_attacking(&state, "5s"));
+    }
+
+    #[test]
+    fn parried_moves_cant_be_canceled() {
+        // Pressed late in the attacker's freeze after the parry, so it's still
+        // buffered when the jab's active frames resume.
+        let jab = then("5a", 25, &["5b"]);
+        assert!(!performs(new_close_fight(), &jab, &["5", "4s"], "5b"));
+    }
+
+    #[test]
+    fn buffered_motions_come_out_on_the_first_cancellable_frame() {
+        let mut state = new_close_fight();
+        state.player_1.character_stats.super_meter = state.player_1.max_super_meter();
+        state.update(input("5s"), input("5"));
+        while state.player_1.freeze_frames == 0 {
+            state.update(input("5"), input("5"));
+        }
+
+        // The whole motion is input during the hitstop.
+        for notation in ["2", "3", "6cs"] {
+            state.update(input(notation), input("5"));
+        }
+        while state.player_1.freeze_frames > 0 {
+            state.update(input("5"), input("5"));
+        }
+        assert!(is_attacking(&state, "5s"));
+
+        state.update(input("5"), input("5"));
+        assert!(is_attacking(&state, "236cs"));
+    }
+}
@@ -1,12 +1,149 @@
-use super::Move;
+use super::{KnockdownKind, Move, MoveType, ThrowData};
 
+#[derive(Hash, Clone, Copy)]
 pub enum CharacterState {
     Idle,
-    Walking,
+    Walking {
+        backward: bool,
+    },
+    Crouching,
+    /// A grounded dash, which `frame` counts up through from 0.
+    Dashing {
+        backward: bool,
+        frame: usize,
+    },
     Jumping,
-    Attacking(&'static Move),
-    Blockstun,
+    /// Recovery after landing from a jump, before the character can act.
+    Landing(usize),
+    /// A burst forwards paid for with boost meter, counting down the frames
+    /// left. Can be attacked out of at any point.
+    Boosting(usize),
+    /// `frame` counts up from 0 on the first frame of the move.
+    Attacking {
+        attack: &'static Move,
+        frame: usize,
+        contact: Option<Contact>,
+    },
+    /// Both sides of a throw which has connected, counting up together
+    /// from 0 on the frame the throw connected.
+    Throwing {
+        throw: &'static ThrowData,
+        frame: usize,
+    },
+    Thrown {
+        throw: &'static ThrowData,
+        frame: usize,
+    },
+    /// Holding a block pose because an attack is nearby, before it connects.
+    Guarding,
+    Blockstun(usize),
     Hitstun(usize),
     KnockedDown(usize),
-    Juggle,
+    /// Getting up after a knockdown, invincible to strikes and throws.
+    WakingUp(usize),
+    /// Hit into the air, falling until they land. The bounces and knockdown
+    /// come from the last strike to hit, and each bounce only happens once.
+    Juggle {
+        ground_bounce: bool,
+        wall_bounce: bool,
+        knockdown: KnockdownKind,
+    },
+}
+
+/// What a character may do of their own accord in a state.
+pub struct AllowedActions {
+    /// The least important type of move that can be started, so
+    /// `Some(MoveType::Special)` also allows supers, or None for no moves.
+    pub moves: Option<MoveType>,
+    /// Whether walking, crouching, jumping and dashing are allowed.
+    pub movement: bool,
+    pub block: bool,
+    /// Whether boost meter can be spent, which cancels the current state.
+    pub boost: bool,
+}
+
+/// Records the segment of a move which last connected, so that
+/// each activation of a hitbox can only hit once.
+#[derive(Hash, Clone, Copy)]
+pub struct Contact {
+    pub segment: usize,
+    pub blocked: bool,
+    /// Parried moves can't be canceled.
+    pub parried: bool,
+}
+
+impl CharacterState {
+    pub fn is_airborne(&self) -> bool {
+        match self {
+            CharacterState::Jumping | CharacterState::Juggle { .. } => true,
+            CharacterState::Attacking { attack, .. } => attack.command.airborne,
+            _ => false,
+        }
+    }
+
+    pub fn allowed_actions(&self) -> AllowedActions {
+        let (moves, movement, block, boost) = match self {
+            CharacterState::Idle | CharacterState::Walking { .. } | CharacterState::Crouching => {
+                (Some(MoveType::Normal), true, true, true)
+            }
+            CharacterState::Guarding => (Some(MoveType::Normal), false, true, true),
+            CharacterState::Jumping => (Some(MoveType::Normal), false, false, false),
+            // Forward dashes can be attacked out of, backdashes have to finish.
+            CharacterState::Dashing { backward, .. } => (
+                Some(MoveType::Normal).filter(|_| !backward),
+                false,
+                false,
+                true,
+            ),
+            CharacterState::Boosting(_) => (Some(MoveType::Normal), false, false, false),
+            // Boosting out of a grounded move cancels it, hit or not.
+            CharacterState::Attacking { attack, .. } => {
+                (None, false, false, !attack.command.airborne)
+            }
+            CharacterState::Blockstun(_) => (None, false, true, false),
+            CharacterState::Throwing { .. }
+            | CharacterState::Thrown { .. }
+            | CharacterState::Landing(_)
+            | CharacterState::Hitstun(_)
+            | CharacterState::KnockedDown(_)
+            | CharacterState::WakingUp(_)
+            | CharacterState::Juggle { .. } => (None, false, false, false),
+        };
+
+        AllowedActions {
+            moves,
+            movement,
+            block,
+            boost,
+        }
+    }
+
+    pub fn can_be_hit(&self) -> bool {
+        !matches!(
+            self,
+            CharacterState::KnockedDown(_)
+                | CharacterState::WakingUp(_)
+                | CharacterState::Throwing { .. }
+                | CharacterState::Thrown { .. }
+        )
+    }
+
+    /// Throws only connect on grounded characters who aren't in stun.
+    pub fn can_be_thrown(&self) -> bool {
+        match self {
+            CharacterState::Idle
+            | CharacterState::Walking { .. }
+            | CharacterState::Crouching
+            | CharacterState::Dashing { .. }
+            | CharacterState::Boosting(_)
+            | CharacterState::Landing(_)
+            | CharacterState::Guarding => true,
+            CharacterState::Attacking { attack, .. } => !attack.command.airborne,
+            _ => false,
+        }
+    }
+
+    pub fn can_block(&self) -> bool {
+        self.allowed_actions().block
+    }
 }
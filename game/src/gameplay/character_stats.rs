@@ -1,5 +1,6 @@
 use crate::db::CharacterDefinition;
 
+#[derive(Hash, Clone, Copy)]
 pub struct CharacterStats {
     pub health: usize,
     pub boost_meter: usize,
@@ -10,7 +11,7 @@ impl CharacterStats {
     pub fn new(definition: &'static CharacterDefinition) -> Self {
         Self {
             health: definition.max_hp,
-            boost_meter: 1000,
+            boost_meter: definition.max_boost_meter,
             super_meter: 0,
         }
     }
@@ -1,7 +1,9 @@
-use super::rect::Rect;
+use glam::IVec2;
+
+use super::{Facing, rect::Rect};
 
 pub struct CollisionBoxes {
-    data: &'static [Rect],
+    pub data: &'static [Rect],
 }
 
 impl CollisionBoxes {
@@ -16,4 +18,26 @@ impl CollisionBoxes {
 
         false
     }
+
+    /// Like `collides`, but with each set of boxes placed at a position in the
+    /// world and mirrored when facing left.
+    pub fn collides_at(
+        &self,
+        position: IVec2,
+        facing: Facing,
+        other: &CollisionBoxes,
+        other_position: IVec2,
+        other_facing: Facing,
+    ) -> bool {
+        for rect in self.data.iter() {
+            let rect = rect.placed(position, facing);
+            for target in other.data.iter() {
+                if rect.collides(&target.placed(other_position, other_facing)) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
 }
@@ -1,20 +1,126 @@
+use std::cmp::Reverse;
+
+use super::{CancelProperties, CharacterState, InputBuffer, MoveData};
+
+/// How many frames ago a command may have been completed and still come out.
+pub(super) const COMMAND_BUFFER_WINDOW: usize = 3;
+/// Like `COMMAND_BUFFER_WINDOW`, but long enough to cover a move's hitstop.
+const CANCEL_BUFFER_WINDOW: usize = 16;
+const MAX_MOTION_STEPS: usize = 8;
+
 pub struct CommandList {
     pub moves: &'static [Move],
 }
 
 impl CommandList {
-    pub fn get_move(&self, input: InputCommand) -> Option<&Move> {
-        self.moves.iter().find(|m| m.command == input)
+    /// Returns the move whose command was completed by the most recent inputs.
+    ///
+    /// When several moves match, commands using more buttons win, then supers
+    /// beat specials which beat normals, and longer motions beat shorter ones.
+    /// Button count comes first because a chord is pressed for the move it
+    /// names, such as AS for a throw, which would otherwise lose to the
+    /// special its S also starts.
+    /// Single direction commands may also fall back to a simpler direction,
+    /// ie. 3a to 2a and then 5a, but exact matches are preferred. Crouching
+    /// characters never fall back to standing moves.
+    ///
+    /// Only moves the state allows starting, and which cost no more than
+    /// the super meter available, are returned.
+    pub fn get_move(
+        &self,
+        buffer: &InputBuffer,
+        state: &CharacterState,
+        meter: usize,
+    ) -> Option<&'static Move> {
+        let lowest = state.allowed_actions().moves?;
+        self.find_move(buffer, state, COMMAND_BUFFER_WINDOW, |m| {
+            m.data.move_type >= lowest && m.data.meter_cost <= meter
+        })
+    }
+
+    /// Like `get_move`, but only returns moves which `current` can be canceled
+    /// into, and accepts commands input further back so that anything buffered
+    /// during hitstop comes out on the first cancellable frame.
+    pub fn get_cancel(
+        &self,
+        buffer: &InputBuffer,
+        state: &CharacterState,
+        current: &Move,
+        cancel_properties: &CancelProperties,
+        meter: usize,
+    ) -> Option<&'static Move> {
+        self.find_move(buffer, state, CANCEL_BUFFER_WINDOW, |next| {
+            cancel_properties.allows(current, next) && next.data.meter_cost <= meter
+        })
+    }
+
+    fn find_move(
+        &self,
+        buffer: &InputBuffer,
+        state: &CharacterState,
+        window: usize,
+        allowed: impl Fn(&Move) -> bool,
+    ) -> Option<&'static Move> {
+        let airborne = state.is_airborne();
+        let max_fallback = if matches!(state, CharacterState::Crouching) {
+            1
+        } else {
+            2
+        };
+
+        self.moves
+            .iter()
+            .filter(|m| m.command.airborne == airborne && allowed(m))
+            .filter_map(|m| {
+                let fallback = command_fallback(&m.command, buffer, window, max_fallback)?;
+                let priority = (
+                    Reverse(m.command.button.map_or(0, ButtonInput::button_count)),
+                    Reverse(m.data.move_type),
+                    Reverse(m.command.motion.steps().len()),
+                    fallback,
+                );
+                Some((priority, m))
+            })
+            .min_by_key(|(priority, _)| *priority)
+            .map(|(_, m)| m)
     }
 }
 
-#[derive(PartialEq, Eq, Default, Clone, Copy)]
+/// Returns how far down the directional fallback the command was matched,
+/// where 0 is an exact match, or None if it wasn't input at all or would
+/// need to fall back further than `max_fallback`.
+fn command_fallback(
+    command: &InputCommand,
+    buffer: &InputBuffer,
+    window: usize,
+    max_fallback: usize,
+) -> Option<usize> {
+    if buffer.matches(command, window) {
+        return Some(0);
+    }
+
+    let (button, [step]) = (command.button?, command.motion.steps()) else {
+        return None;
+    };
+    if step.charge {
+        return None;
+    }
+
+    let held = buffer.pressed_direction(button, window)?;
+    held.fallbacks()
+        .iter()
+        .take(max_fallback)
+        .position(|direction| *direction == step.direction)
+        .map(|index| index + 1)
+}
+
+#[derive(PartialEq, Eq, Hash, Default, Clone, Copy)]
 pub struct DirectionInput {
     pub vertical: VerticalInput,
     pub horizontal: HorizontalInput,
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum ButtonInput {
     A, // Light
     B, // Medium
@@ -26,7 +132,7 @@ pub enum ButtonInput {
     CS, // Super
 }
 
-#[derive(PartialEq, Eq, Default, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Default, Clone, Copy)]
 pub enum VerticalInput {
     #[default]
     Neutral,
@@ -34,7 +140,7 @@ pub enum VerticalInput {
     Down,
 }
 
-#[derive(PartialEq, Eq, Default, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Default, Clone, Copy)]
 pub enum HorizontalInput {
     #[default]
     Neutral,
@@ -42,10 +148,42 @@ pub enum HorizontalInput {
     Backward,
 }
 
-#[derive(PartialEq, Eq)]
-pub struct InputCommand {
+/// A single direction within a motion. Charge steps must be held
+/// for `CHARGE_FRAMES` before the next step is input.
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct MotionStep {
     pub direction: DirectionInput,
-    pub button: ButtonInput,
+    pub charge: bool,
+}
+
+impl MotionStep {
+    const NEUTRAL: Self = Self {
+        direction: DirectionInput {
+            vertical: VerticalInput::Neutral,
+            horizontal: HorizontalInput::Neutral,
+        },
+        charge: false,
+    };
+}
+
+/// A sequence of directions, oldest first, such as 2 3 6 for a quarter circle.
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Motion {
+    steps: [MotionStep; MAX_MOTION_STEPS],
+    len: usize,
+}
+
+impl Motion {
+    pub fn steps(&self) -> &[MotionStep] {
+        &self.steps[..self.len]
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+pub struct InputCommand {
+    pub airborne: bool,
+    pub motion: Motion,
+    pub button: Option<ButtonInput>,
 }
 
 impl DirectionInput {
@@ -93,9 +231,46 @@ impl DirectionInput {
             _ => panic!(),
         }
     }
+
+    /// Simpler directions to try when no command matches this one exactly,
+    /// dropping the horizontal component and then everything, ie. 3 -> 2 -> 5.
+    pub fn fallbacks(&self) -> [DirectionInput; 2] {
+        [
+            DirectionInput {
+                vertical: self.vertical,
+                horizontal: HorizontalInput::Neutral,
+            },
+            DirectionInput::default(),
+        ]
+    }
+
+    /// Returns true if holding this direction also holds every
+    /// non-neutral component of `other`, ie. 1 includes both 2 and 4.
+    pub fn includes(&self, other: &DirectionInput) -> bool {
+        let vertical = other.vertical == VerticalInput::Neutral || other.vertical == self.vertical;
+        let horizontal =
+            other.horizontal == HorizontalInput::Neutral || other.horizontal == self.horizontal;
+        vertical && horizontal
+    }
 }
 
 impl ButtonInput {
+    /// Whether a normal using this button can chain into one using `next`,
+    /// going from light to medium to heavy.
+    pub fn chains_into(self, next: ButtonInput) -> bool {
+        matches!(
+            (self, next),
+            (ButtonInput::A, ButtonInput::B | ButtonInput::C) | (ButtonInput::B, ButtonInput::C)
+        )
+    }
+
+    pub fn button_count(self) -> usize {
+        match self {
+            ButtonInput::A | ButtonInput::B | ButtonInput::C | ButtonInput::S => 1,
+            ButtonInput::AS | ButtonInput::BS | ButtonInput::CS => 2,
+        }
+    }
+
     pub const fn from_notation(input: &'static str) -> Self {
         let bytes = input.as_bytes(); // Check if `as_bytes` is const in your Rust version.
         Self::from_notation_bytes(bytes)
@@ -131,20 +306,61 @@ impl ButtonInput {
 }
 
 impl InputCommand {
+    /// Parses numpad notation, ie. "5a", "236s", "[4]6s" or "66".
+    /// Directions in square brackets must be charged, and a "j." prefix
+    /// marks the command as only usable in the air.
     pub const fn from_notation(input: &'static str) -> Self {
         let bytes = input.as_bytes();
-        let button = match bytes.len() {
-            2 => ButtonInput::from_notation_bytes(&[bytes[1]]),
-            3 => ButtonInput::from_notation_bytes(&[bytes[1], bytes[2]]),
+        let mut steps = [MotionStep::NEUTRAL; MAX_MOTION_STEPS];
+        let mut len = 0;
+        let airborne = bytes.len() > 2 && bytes[0] == b'j' && bytes[1] == b'.';
+        let mut i = if airborne { 2 } else { 0 };
+
+        while i < bytes.len() {
+            let (direction, charge, width) = match bytes[i] {
+                b'1'..=b'9' => (bytes[i], false, 1),
+                b'[' => {
+                    if i + 2 >= bytes.len() || bytes[i + 2] != b']' {
+                        panic!("Invalid charge input.");
+                    }
+                    (bytes[i + 1], true, 3)
+                }
+                _ => break,
+            };
+
+            if len == MAX_MOTION_STEPS {
+                panic!("Motion too long.");
+            }
+
+            steps[len] = MotionStep {
+                direction: DirectionInput::from_notation(&(direction as char)),
+                charge,
+            };
+            len += 1;
+            i += width;
+        }
+
+        if len == 0 {
+            panic!("Missing direction.");
+        }
+
+        let button = match bytes.len() - i {
+            0 => None,
+            1 => Some(ButtonInput::from_notation_bytes(&[bytes[i]])),
+            2 => Some(ButtonInput::from_notation_bytes(&[bytes[i], bytes[i + 1]])),
             _ => panic!("Invalid input."),
         };
+
         Self {
-            direction: DirectionInput::from_notation(&(bytes[0] as char)),
+            airborne,
+            motion: Motion { steps, len },
             button,
         }
     }
 }
 
+/// Ordered from lowest to highest priority.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum MoveType {
     Normal,
     Special,
@@ -153,5 +369,97 @@ pub enum MoveType {
 
 pub struct Move {
     pub command: InputCommand,
-    pub move_type: MoveType,
+    pub data: MoveData,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ButtonInput, CharacterState, InputBuffer, MoveData, testing::input};
+    use super::{CommandList, InputCommand, Move, MoveType};
+
+    const fn test_move(notation: &'static str, move_type: MoveType) -> Move {
+        Move {
+            command: InputCommand::from_notation(notation),
+            data: MoveData {
+                move_type,
+                whiff_meter_gain: 0,
+                meter_cost: 0,
+                super_flash_frames: 0,
+                frame_data: &[],
+            },
+        }
+    }
+
+    static COMMANDS: CommandList = CommandList {
+        moves: &[
+            test_move("5a", MoveType::Normal),
+            test_move("2a", MoveType::Normal),
+            test_move("5b", MoveType::Normal),
+            test_move("5as", MoveType::Normal),
+            test_move("2s", MoveType::Special),
+            test_move("5s", MoveType::Special),
+            test_move("6s", MoveType::Special),
+            test_move("236s", MoveType::Special),
+            test_move("236cs", MoveType::Super),
+        ],
+    };
+
+    /// The command of the move started by the inputs, one per frame.
+    fn command(state: &CharacterState, inputs: &[&str]) -> Option<&'static InputCommand> {
+        let mut buffer = InputBuffer::new();
+        for notation in inputs {
+            buffer.push(input(notation));
+        }
+
+        COMMANDS
+            .get_move(&buffer, state, 0)
+            .map(|found| &found.command)
+    }
+
+    fn is(found: Option<&InputCommand>, notation: &'static str) -> bool {
+        found == Some(&InputCommand::from_notation(notation))
+    }
+
+    #[test]
+    fn prefers_supers_then_longer_motions() {
+        let idle = &CharacterState::Idle;
+        assert!(is(command(idle, &["2", "3", "6cs"]), "236cs"));
+        assert!(is(command(idle, &["2", "3", "6s"]), "236s"));
+        assert!(is(command(idle, &["5", "2s"]), "2s"));
+
+        // Both buttons of BS are pressed, and the special wins over the normal.
+        assert!(is(command(idle, &["5", "5bs"]), "5s"));
+    }
+
+    #[test]
+    fn chords_beat_the_moves_their_buttons_start() {
+        // The throw is a normal, but AS is pressed for it rather than for 5s.
+        assert!(is(command(&CharacterState::Idle, &["5", "5as"]), "5as"));
+    }
+
+    #[test]
+    fn falls_back_to_simpler_directions_when_standing() {
+        let idle = &CharacterState::Idle;
+        assert!(is(command(idle, &["5", "3a"]), "2a"));
+        assert!(is(command(idle, &["5", "3b"]), "5b"));
+        assert!(is(command(idle, &["5", "2a"]), "2a"));
+    }
+
+    #[test]
+    fn crouching_never_falls_back_to_standing_normals() {
+        let crouching = &CharacterState::Crouching;
+        assert!(is(command(crouching, &["2", "3a"]), "2a"));
+        assert!(command(crouching, &["2", "3b"]).is_none());
+        assert!(command(crouching, &["2", "2b"]).is_none());
+    }
+
+    #[test]
+    fn parses_air_commands() {
+        let air = InputCommand::from_notation("j.2c");
+        assert!(air.airborne);
+        assert!(air.button == Some(ButtonInput::C));
+        assert!(air.motion == InputCommand::from_notation("2c").motion);
+
+        assert!(!InputCommand::from_notation("5a").airborne);
+    }
 }
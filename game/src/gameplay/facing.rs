@@ -0,0 +1,27 @@
+use super::HorizontalInput;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Facing {
+    Left,
+    Right,
+}
+
+impl Facing {
+    /// 1 when facing right and -1 when facing left, for moving forwards.
+    pub fn sign(self) -> isize {
+        match self {
+            Facing::Left => -1,
+            Facing::Right => 1,
+        }
+    }
+
+    /// Converts raw left/right into a direction relative to this facing.
+    /// Holding both (or neither) is treated as neutral.
+    pub fn horizontal_input(self, left: bool, right: bool) -> HorizontalInput {
+        match (left, right, self) {
+            (true, false, Facing::Right) | (false, true, Facing::Left) => HorizontalInput::Backward,
+            (true, false, Facing::Left) | (false, true, Facing::Right) => HorizontalInput::Forward,
+            _ => HorizontalInput::Neutral,
+        }
+    }
+}
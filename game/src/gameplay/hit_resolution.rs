@@ -0,0 +1,198 @@
+use super::{
+    AttackData, CharacterState, Contact, Facing, Hitboxes, MatchState, PlayerGameState, StrikeData,
+    parry::parry_strike,
+};
+
+/// A strike which connected with the opponent this frame.
+struct Strike {
+    segment: usize,
+    data: &'static StrikeData,
+}
+
+impl MatchState {
+    /// Tests each player's active hitboxes against their opponent's hurtboxes,
+    /// then applies any strikes which connected. Both players are checked
+    /// before anything is applied so that simultaneous hits trade.
+    pub(super) fn resolve_hits(&mut self) {
+        let (p1_facing, p2_facing) = self.facings();
+        let p1_strike = self
+            .player_1
+            .find_strike(p1_facing, &self.player_2, p2_facing);
+        let p2_strike = self
+            .player_2
+            .find_strike(p2_facing, &self.player_1, p1_facing);
+
+        if let Some(strike) = p1_strike {
+            apply_strike(&mut self.player_1, p1_facing, &mut self.player_2, strike);
+        }
+
+        if let Some(strike) = p2_strike {
+            apply_strike(&mut self.player_2, p2_facing, &mut self.player_1, strike);
+        }
+    }
+}
+
+fn apply_strike(
+    attacker: &mut PlayerGameState,
+    facing: Facing,
+    defender: &mut PlayerGameState,
+    strike: Strike,
+) {
+    if let Some(parry) = defender.parries(strike.data) {
+        parry_strike(attacker, defender, strike.segment, parry);
+        return;
+    }
+
+    let blocked = defender.blocks(strike.data);
+    let hitstop = if blocked {
+        strike.data.block_hitstop_frames
+    } else {
+        strike.data.hit_hitstop_frames
+    };
+
+    if let CharacterState::Attacking { contact, .. } = &mut attacker.character_state {
+        *contact = Some(Contact {
+            segment: strike.segment,
+            blocked,
+            parried: false,
+        });
+    }
+    attacker.freeze_frames = hitstop;
+
+    attacker.gain_meter(if blocked {
+        strike.data.block_meter_gain
+    } else {
+        strike.data.hit_meter_gain
+    });
+    defender.receive_strike(facing, strike.data, blocked);
+}
+
+impl PlayerGameState {
+    /// Applies the defender's side of a strike coming from the `facing`
+    /// direction, whether from a character or a projectile.
+    pub(super) fn receive_strike(&mut self, facing: Facing, strike: &StrikeData, blocked: bool) {
+        self.freeze_frames = if blocked {
+            strike.block_hitstop_frames
+        } else {
+            strike.hit_hitstop_frames
+        };
+        self.gain_meter(strike.defender_meter_gain);
+
+        if blocked {
+            self.change_state(CharacterState::Blockstun(strike.block_stun));
+        } else {
+            let health = &mut self.character_stats.health;
+            *health = health.saturating_sub(strike.damage);
+            self.take_hit(strike);
+        }
+        self.vel_x = facing.sign() * strike.knockback.x as isize;
+    }
+
+    /// Returns the strike landing on the opponent this frame, if the current
+    /// segment's hitboxes overlap their hurtboxes and haven't already hit.
+    fn find_strike(
+        &self,
+        facing: Facing,
+        opponent: &PlayerGameState,
+        opponent_facing: Facing,
+    ) -> Option<Strike> {
+        if !opponent.character_state.can_be_hit() {
+            return None;
+        }
+
+        let CharacterState::Attacking {
+            attack,
+            frame,
+            contact,
+        } = &self.character_state
+        else {
+            return None;
+        };
+
+        let (segment, frame_data) = attack.data.get_frame_data(*frame)?;
+        if contact.is_some_and(|contact| contact.segment == segment) {
+            return None;
+        }
+
+        let hitboxes = frame_data.hitboxes.as_ref()?;
+        let AttackData::Strike(data) = &hitboxes.attack_data else {
+            return None;
+        };
+        if !opponent.can_be_juggled_by(data) {
+            return None;
+        }
+
+        self.hitboxes_overlap(hitboxes, facing, opponent, opponent_facing)
+            .then_some(Strike { segment, data })
+    }
+
+    /// Whether any of the hitboxes overlap any of the opponent's hurtboxes.
+    pub(super) fn hitboxes_overlap(
+        &self,
+        hitboxes: &Hitboxes,
+        facing: Facing,
+        opponent: &PlayerGameState,
+        opponent_facing: Facing,
+    ) -> bool {
+        let hurtboxes = opponent.hurtboxes();
+        hitboxes.collision.iter().any(|hitbox| {
+            hurtboxes.collision.iter().any(|hurtbox| {
+                hitbox.collides_at(
+                    self.position(),
+                    facing,
+                    hurtbox,
+                    opponent.position(),
+                    opponent_facing,
+                )
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        AttackData, CharacterState,
+        testing::{find_move, hold, new_close_fight},
+    };
+
+    fn damage(notation: &'static str) -> usize {
+        let hitboxes = find_move(notation).data.frame_data[1].hitboxes.as_ref();
+        match hitboxes.map(|hitboxes| &hitboxes.attack_data) {
+            Some(AttackData::Strike(strike)) => strike.damage,
+            _ => panic!("{notation} should be a strike"),
+        }
+    }
+
+    #[test]
+    fn each_segment_hits_only_once() {
+        let mut state = new_close_fight();
+        let max_hp = state.player_2.character.max_hp;
+
+        // The jab's hitbox overlaps player 2 for all of its active frames.
+        hold(&mut state, 1, "5a", "5");
+        hold(&mut state, 30, "5", "5");
+
+        assert_eq!(state.player_2.character_stats.health, max_hp - damage("5a"));
+    }
+
+    #[test]
+    fn hits_on_the_same_frame_trade() {
+        let mut state = new_close_fight();
+        let max_hp = state.player_1.character.max_hp;
+
+        hold(&mut state, 1, "5a", "5a");
+        hold(&mut state, 6, "5", "5");
+
+        assert_eq!(state.player_1.character_stats.health, max_hp - damage("5a"));
+        assert_eq!(state.player_2.character_stats.health, max_hp - damage("5a"));
+        assert!(matches!(
+            state.player_1.character_state,
+            CharacterState::Hitstun(_)
+        ));
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Hitstun(_)
+        ));
+    }
+}
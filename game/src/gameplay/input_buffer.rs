@@ -1,10 +1,16 @@
 use std::collections::VecDeque;
 
-use super::{CommandList, DirectionInput, InputCommand, Move};
+use super::{
+    ButtonInput, DirectionInput, HorizontalInput, InputCommand, Motion, MotionStep, VerticalInput,
+};
 
 const INPUT_BUFFER_LENGTH: usize = 32;
+/// Maximum frames allowed between the end of one motion step and the start of the next.
+const MOTION_LENIENCY: usize = 8;
+/// Frames a charge direction must be held for.
+const CHARGE_FRAMES: usize = 30;
 
-#[derive(Default)]
+#[derive(Default, Hash, Clone, Copy)]
 pub struct FrameCommandState {
     pub frame_count: usize,
     pub state: InputState,
@@ -19,7 +25,7 @@ impl FrameCommandState {
     }
 }
 
-#[derive(Default, PartialEq, Eq, Clone, Copy)]
+#[derive(Default, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct InputState {
     pub direction: DirectionInput,
     pub a_pressed: bool,
@@ -28,15 +34,129 @@ pub struct InputState {
     pub s_pressed: bool,
 }
 
+impl InputState {
+    pub fn button_held(&self, button: ButtonInput) -> bool {
+        match button {
+            ButtonInput::A => self.a_pressed,
+            ButtonInput::B => self.b_pressed,
+            ButtonInput::C => self.c_pressed,
+            ButtonInput::S => self.s_pressed,
+            ButtonInput::AS => self.a_pressed && self.s_pressed,
+            ButtonInput::BS => self.b_pressed && self.s_pressed,
+            ButtonInput::CS => self.c_pressed && self.s_pressed,
+        }
+    }
+
+    /// Reads a numpad direction relative to the way the player faces,
+    /// followed by any of the buttons a, b, c and s, such as `2b`.
+    pub fn from_notation(notation: &str) -> Option<Self> {
+        let mut chars = notation.chars();
+        let direction = chars
+            .next()
+            .filter(|direction| ('1'..='9').contains(direction))?;
+        let mut input = InputState {
+            direction: DirectionInput::from_notation(&direction),
+            ..InputState::default()
+        };
+
+        for button in chars {
+            let pressed = match button {
+                'a' => &mut input.a_pressed,
+                'b' => &mut input.b_pressed,
+                'c' => &mut input.c_pressed,
+                's' => &mut input.s_pressed,
+                _ => return None,
+            };
+            *pressed = true;
+        }
+
+        Some(input)
+    }
+
+    /// Packs the inputs into a single byte, with the vertical direction in
+    /// the lowest two bits, then the horizontal direction, then A, B, C and S.
+    pub fn to_byte(&self) -> u8 {
+        let vertical = match self.direction.vertical {
+            VerticalInput::Neutral => 0,
+            VerticalInput::Up => 1,
+            VerticalInput::Down => 2,
+        };
+        let horizontal = match self.direction.horizontal {
+            HorizontalInput::Neutral => 0,
+            HorizontalInput::Forward => 1,
+            HorizontalInput::Backward => 2,
+        };
+
+        vertical
+            | horizontal << 2
+            | (self.a_pressed as u8) << 4
+            | (self.b_pressed as u8) << 5
+            | (self.c_pressed as u8) << 6
+            | (self.s_pressed as u8) << 7
+    }
+
+    /// Unpacks inputs packed by `to_byte`, or None if the byte isn't valid.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        let vertical = match byte & 0b11 {
+            0 => VerticalInput::Neutral,
+            1 => VerticalInput::Up,
+            2 => VerticalInput::Down,
+            _ => return None,
+        };
+        let horizontal = match byte >> 2 & 0b11 {
+            0 => HorizontalInput::Neutral,
+            1 => HorizontalInput::Forward,
+            2 => HorizontalInput::Backward,
+            _ => return None,
+        };
+
+        Some(Self {
+            direction: DirectionInput {
+                vertical,
+                horizontal,
+            },
+            a_pressed: byte & 1 << 4 != 0,
+            b_pressed: byte & 1 << 5 != 0,
+            c_pressed: byte & 1 << 6 != 0,
+            s_pressed: byte & 1 << 7 != 0,
+        })
+    }
+}
+
+/// A span of frames where the same direction was held. Ages are
+/// counted in frames backwards from the current frame, which is age 0.
+struct DirectionRun {
+    direction: DirectionInput,
+    start_age: usize,
+    end_age: usize,
+}
+
+impl DirectionRun {
+    fn satisfies(&self, step: &MotionStep) -> bool {
+        if step.charge {
+            self.direction.includes(&step.direction)
+        } else {
+            self.direction == step.direction
+        }
+    }
+}
+
+#[derive(Hash, Clone)]
 pub struct InputBuffer {
     buffer: VecDeque<FrameCommandState>,
+    /// Frames since the presses in the buffer were last used up by
+    /// `consume`. Presses at least this old are ignored.
+    consumed_age: Option<usize>,
 }
 
 impl InputBuffer {
     pub fn new() -> Self {
         let mut buffer = VecDeque::with_capacity(INPUT_BUFFER_LENGTH);
         buffer.push_back(FrameCommandState::default());
-        Self { buffer }
+        Self {
+            buffer,
+            consumed_age: None,
+        }
     }
 
     pub fn get_last_entry(&self) -> &FrameCommandState {
@@ -44,6 +164,10 @@ impl InputBuffer {
     }
 
     pub fn push(&mut self, input_state: InputState) {
+        if let Some(age) = &mut self.consumed_age {
+            *age = age.saturating_add(1);
+        }
+
         let last = self.buffer.back_mut().unwrap();
         if last.state == input_state {
             last.frame_count += 1;
@@ -56,4 +180,229 @@ impl InputBuffer {
 
         self.buffer.push_back(FrameCommandState::new(input_state));
     }
+
+    /// Uses up every press in the buffer, so none of them can be read again.
+    pub fn consume(&mut self) {
+        self.consumed_age = Some(0);
+    }
+
+    /// Returns true if the command was completed within the last `window` frames.
+    pub fn matches(&self, command: &InputCommand, window: usize) -> bool {
+        let anchor_age = match command.button {
+            Some(button) => self.button_press(button, window).map(|(age, _)| age),
+            None => self
+                .direction_runs()
+                .next()
+                .map(|run| run.start_age)
+                .filter(|age| *age <= window),
+        };
+
+        anchor_age.is_some_and(|age| self.matches_motion(&command.motion, age))
+    }
+
+    /// Returns true if the button was pressed within the last `window` frames.
+    pub fn pressed(&self, button: ButtonInput, window: usize) -> bool {
+        self.button_press(button, window).is_some()
+    }
+
+    /// Returns the direction held when the button was pressed, if it was
+    /// pressed within the last `window` frames.
+    pub fn pressed_direction(&self, button: ButtonInput, window: usize) -> Option<DirectionInput> {
+        self.button_press(button, window)
+            .map(|(_, direction)| direction)
+    }
+
+    /// Returns how many frames ago the button was pressed, and the direction
+    /// held at the time, if it was pressed within the last `window` frames.
+    fn button_press(&self, button: ButtonInput, window: usize) -> Option<(usize, DirectionInput)> {
+        let mut entries = self.buffer.iter().rev().peekable();
+        let mut age = 0;
+
+        while let Some(entry) = entries.next() {
+            let start_age = age + entry.frame_count;
+            if start_age > window
+                || self
+                    .consumed_age
+                    .is_some_and(|consumed| start_age >= consumed)
+            {
+                return None;
+            }
+
+            let older = entries.peek()?;
+            if entry.state.button_held(button) && !older.state.button_held(button) {
+                return Some((start_age, entry.state.direction));
+            }
+
+            age = start_age + 1;
+        }
+
+        None
+    }
+
+    /// Scans backwards from `anchor_age`, matching the final step of the
+    /// motion first, with each earlier step allowed up to `MOTION_LENIENCY`
+    /// frames before the step after it.
+    fn matches_motion(&self, motion: &Motion, anchor_age: usize) -> bool {
+        let mut runs = self
+            .direction_runs()
+            .skip_while(|run| run.start_age < anchor_age)
+            .peekable();
+        let mut steps = motion.steps().iter().rev();
+
+        // The final step must be held when the command is completed.
+        let (Some(last_step), Some(last_run)) = (steps.next(), runs.next()) else {
+            return false;
+        };
+        if !last_run.satisfies(last_step) {
+            return false;
+        }
+        let mut next_start_age = last_run.start_age;
+
+        for step in steps {
+            let latest_end_age = next_start_age + 1 + MOTION_LENIENCY;
+            let Some(run) = runs
+                .by_ref()
+                .take_while(|run| run.end_age <= latest_end_age)
+                .find(|run| run.satisfies(step))
+            else {
+                return false;
+            };
+
+            let mut start_age = run.start_age;
+            if step.charge {
+                while let Some(run) = runs.next_if(|run| run.satisfies(step)) {
+                    start_age = run.start_age;
+                }
+
+                if start_age + 1 - run.end_age < CHARGE_FRAMES {
+                    return false;
+                }
+            }
+
+            next_start_age = start_age;
+        }
+
+        true
+    }
+
+    /// Iterates over runs of held directions, newest first.
+    fn direction_runs(&self) -> impl Iterator<Item = DirectionRun> + '_ {
+        let mut entries = self.buffer.iter().rev().peekable();
+        let mut age = 0;
+
+        std::iter::from_fn(move || {
+            let first = entries.next()?;
+            let direction = first.state.direction;
+            let end_age = age;
+            age += first.frame_count + 1;
+
+            while let Some(entry) = entries.next_if(|e| e.state.direction == direction) {
+                age += entry.frame_count + 1;
+            }
+
+            Some(DirectionRun {
+                direction,
+                start_age: age - 1,
+                end_age,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{InputCommand, InputState, testing::input};
+    use super::{CHARGE_FRAMES, InputBuffer, MOTION_LENIENCY};
+
+    /// A buffer which held each input for the given number of frames.
+    fn buffer(inputs: &[(&str, usize)]) -> InputBuffer {
+        let mut buffer = InputBuffer::new();
+        for (notation, frames) in inputs {
+            for _ in 0..*frames {
+                buffer.push(input(notation));
+            }
+        }
+        buffer
+    }
+
+    fn matches(command: &'static str, inputs: &[(&str, usize)]) -> bool {
+        buffer(inputs).matches(&InputCommand::from_notation(command), 0)
+    }
+
+    #[test]
+    fn matches_motions() {
+        assert!(matches("236s", &[("5", 10), ("2", 2), ("3", 2), ("6s", 1)]));
+        assert!(matches("623a", &[("5", 10), ("6", 2), ("2", 2), ("3a", 1)]));
+        assert!(matches("66", &[("5", 10), ("6", 2), ("5", 2), ("6", 1)]));
+
+        assert!(!matches("236s", &[("5", 10), ("2", 2), ("6s", 1)]));
+        assert!(!matches("236s", &[("5", 10), ("2", 2), ("3", 2), ("6", 1)]));
+    }
+
+    #[test]
+    fn allows_gaps_between_steps_up_to_the_leniency() {
+        let inside = MOTION_LENIENCY;
+        let outside = MOTION_LENIENCY + 1;
+
+        assert!(matches(
+            "236s",
+            &[("2", 2), ("5", inside), ("3", 2), ("6s", 1)]
+        ));
+        assert!(!matches(
+            "236s",
+            &[("2", 2), ("5", outside), ("3", 2), ("6s", 1)]
+        ));
+
+        assert!(matches(
+            "623a",
+            &[("6", 2), ("5", inside), ("2", 2), ("3a", 1)]
+        ));
+        assert!(!matches(
+            "623a",
+            &[("6", 2), ("5", outside), ("2", 2), ("3a", 1)]
+        ));
+
+        assert!(matches("66", &[("6", 2), ("5", inside), ("6", 1)]));
+        assert!(!matches("66", &[("6", 2), ("5", outside), ("6", 1)]));
+    }
+
+    #[test]
+    fn charges_must_be_held_long_enough() {
+        assert!(matches("[4]6s", &[("4", CHARGE_FRAMES), ("6s", 1)]));
+        assert!(!matches("[4]6s", &[("4", CHARGE_FRAMES - 1), ("6s", 1)]));
+
+        // Switching between directions which include back keeps charging.
+        let switching = [("4", 10), ("1", 10), ("4", CHARGE_FRAMES - 20), ("6s", 1)];
+        assert!(matches("[4]6s", &switching));
+    }
+
+    #[test]
+    fn dashes_need_a_fresh_press() {
+        assert!(!matches("66", &[("5", 10), ("6", 40)]));
+
+        let dash = buffer(&[("5", 10), ("6", 2), ("5", 2), ("6", 20)]);
+        let command = InputCommand::from_notation("66");
+        assert!(!dash.matches(&command, 3));
+        assert!(dash.matches(&command, 20));
+    }
+
+    #[test]
+    fn round_trips_inputs_through_bytes() {
+        for direction in "123456789".chars() {
+            for buttons in 0..16 {
+                let mut notation = direction.to_string();
+                for (bit, button) in "abcs".chars().enumerate() {
+                    if buttons & 1 << bit != 0 {
+                        notation.push(button);
+                    }
+                }
+
+                let input = input(&notation);
+                assert!(InputState::from_byte(input.to_byte()) == Some(input));
+            }
+        }
+
+        assert!(InputState::from_byte(0b0000_0011).is_none());
+        assert!(InputState::from_byte(0b0000_1100).is_none());
+    }
 }
@@ -0,0 +1,147 @@
+use super::{CharacterState, KnockdownKind, PlayerGameState, StrikeData};
+
+/// Juggle points a combo can use before the defender can't be hit again
+/// until they land, so that no juggle can go on forever.
+const JUGGLE_LIMIT: usize = 6;
+/// Upwards speed given to an airborne character hit without any upwards knockback.
+const AIR_HIT_LIFT: isize = 50;
+const GROUND_BOUNCE_VELOCITY: isize = 90;
+const WALL_BOUNCE_LIFT: isize = 60;
+
+impl PlayerGameState {
+    /// Puts the character into hitstun, or into a juggle if the strike
+    /// launches them or they were already in the air.
+    pub(super) fn take_hit(&mut self, strike: &StrikeData) {
+        if !matches!(
+            self.character_state,
+            CharacterState::Hitstun(_) | CharacterState::Juggle { .. }
+        ) {
+            self.juggle_points = 0;
+        }
+
+        let launched = strike.knockback.y < 0;
+        if !launched && !self.is_airborne() {
+            self.change_state(CharacterState::Hitstun(strike.hit_stun));
+            return;
+        }
+
+        self.juggle_points += strike.juggle_cost;
+        self.change_state(CharacterState::Juggle {
+            ground_bounce: strike.ground_bounce,
+            wall_bounce: strike.wall_bounce,
+            knockdown: strike.knockdown,
+        });
+        self.vel_y = if launched {
+            strike.knockback.y as isize
+        } else {
+            -AIR_HIT_LIFT
+        };
+    }
+
+    /// Whether the strike can hit the character without going over the
+    /// juggle limit. Characters who aren't being juggled can always be hit.
+    pub(super) fn can_be_juggled_by(&self, strike: &StrikeData) -> bool {
+        !matches!(self.character_state, CharacterState::Juggle { .. })
+            || self.juggle_points + strike.juggle_cost <= JUGGLE_LIMIT
+    }
+
+    /// Bounces a juggled character back off the ground if the last strike
+    /// allows it, otherwise knocks them down.
+    pub(super) fn land_from_juggle(&mut self) {
+        let CharacterState::Juggle {
+            ground_bounce,
+            knockdown,
+            ..
+        } = &mut self.character_state
+        else {
+            return;
+        };
+
+        if *ground_bounce {
+            *ground_bounce = false;
+            self.vel_y = -GROUND_BOUNCE_VELOCITY;
+            return;
+        }
+
+        let frames = match knockdown {
+            KnockdownKind::Soft => self.character.soft_knockdown_frames,
+            KnockdownKind::Hard => self.character.hard_knockdown_frames,
+        };
+        self.vel_x = 0;
+        self.vel_y = 0;
+        self.change_state(CharacterState::KnockedDown(frames));
+    }
+
+    /// Sends a juggled character back the way they came after hitting the
+    /// wall with `velocity`, if the last strike allows it. Returns true if
+    /// they bounced.
+    pub(super) fn bounce_off_wall(&mut self, velocity: isize) -> bool {
+        let CharacterState::Juggle { wall_bounce, .. } = &mut self.character_state else {
+            return false;
+        };
+        if !*wall_bounce {
+            return false;
+        }
+
+        *wall_bounce = false;
+        self.vel_x = -velocity;
+        self.vel_y = self.vel_y.min(-WALL_BOUNCE_LIFT);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, KnockdownKind, MatchState,
+        testing::{hold, input, new_close_fight},
+    };
+    use super::JUGGLE_LIMIT;
+
+    /// The juggle cost of the default character's 5C.
+    const HEAVY_COST: usize = 2;
+
+    /// A fight with player 2 launched high above player 1, having already
+    /// used up `juggle_points`.
+    fn juggled(juggle_points: usize) -> MatchState {
+        let mut state = new_close_fight();
+        state.player_2.pos_y = -300;
+        state.player_2.vel_y = -60;
+        state.player_2.juggle_points = juggle_points;
+        state.player_2.character_state = CharacterState::Juggle {
+            ground_bounce: false,
+            wall_bounce: false,
+            knockdown: KnockdownKind::Soft,
+        };
+        state
+    }
+
+    /// Whether player 1's 5C hits the juggled player 2.
+    fn hits(state: &mut MatchState) -> bool {
+        let health = state.player_2.character_stats.health;
+        state.update(input("5c"), input("5"));
+        hold(state, 20, "5", "5");
+        state.player_2.character_stats.health < health
+    }
+
+    #[test]
+    fn juggles_can_go_up_to_the_limit() {
+        let mut state = juggled(JUGGLE_LIMIT - HEAVY_COST);
+        assert!(hits(&mut state));
+        assert_eq!(state.player_2.juggle_points, JUGGLE_LIMIT);
+    }
+
+    #[test]
+    fn juggles_over_the_limit_whiff() {
+        assert!(!hits(&mut juggled(JUGGLE_LIMIT - HEAVY_COST + 1)));
+        assert!(!hits(&mut juggled(JUGGLE_LIMIT)));
+    }
+
+    #[test]
+    fn grounded_hits_start_a_new_juggle() {
+        let mut state = new_close_fight();
+        state.player_2.juggle_points = JUGGLE_LIMIT;
+        assert!(hits(&mut state));
+        assert!(state.player_2.juggle_points < JUGGLE_LIMIT);
+    }
+}
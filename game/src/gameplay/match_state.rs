@@ -1,15 +1,30 @@
 use std::cmp::Ordering;
 
+use glam::IVec2;
+
 use crate::db::CharacterDefinition;
 
-use super::{CharacterState, CharacterStats, FrameCommandState, InputBuffer, InputState};
+use super::{
+    CharacterState, CharacterStats, Facing, FrameCommandState, FrameData, Hitboxes, Hurtboxes,
+    InputBuffer, InputState, MAX_PROJECTILES, MatchSettings, Owner, Projectile, RoundPhase,
+};
+
+pub(super) const START_POSITION_X: isize = 1000;
 
+#[derive(Clone)]
 pub struct MatchState {
-    pub timer: usize,
+    pub settings: MatchSettings,
+    pub phase: RoundPhase,
+    /// The current round, starting from 1.
+    pub round: usize,
+    /// Frames left in the round, or None when there's no time limit.
+    pub timer: Option<usize>,
     pub player_1: PlayerGameState,
     pub player_2: PlayerGameState,
+    pub projectiles: [Option<Projectile>; MAX_PROJECTILES],
 }
 
+#[derive(Clone)]
 pub struct PlayerGameState {
     pub character: &'static CharacterDefinition,
     pub character_stats: CharacterStats,
@@ -17,17 +32,111 @@ pub struct PlayerGameState {
     pub input_buffer: InputBuffer,
     pub pos_x: isize,
     pub pos_y: isize,
+    pub vel_x: isize,
+    pub vel_y: isize,
+    /// Pushback passed on by an opponent stopped by a wall. Kept apart from
+    /// `vel_x` so that friction slows it even while walking.
+    pub pushback_x: isize,
+    pub facing: Facing,
+    /// Juggle points used up in the combo the character is currently in.
+    pub juggle_points: usize,
 
     pub character_state: CharacterState,
     pub freeze_frames: usize,
+    /// Frames of super flash started this frame, to freeze both players for.
+    pub super_flash: usize,
 }
 
 impl PlayerGameState {
+    pub fn new(character: &'static CharacterDefinition, pos_x: isize, facing: Facing) -> Self {
+        Self {
+            character,
+            character_stats: CharacterStats::new(character),
+            win_count: 0,
+            input_buffer: InputBuffer::new(),
+            pos_x,
+            pos_y: 0,
+            vel_x: 0,
+            vel_y: 0,
+            pushback_x: 0,
+            facing,
+            juggle_points: 0,
+            character_state: CharacterState::Idle,
+            freeze_frames: 0,
+            super_flash: 0,
+        }
+    }
+
+    /// Turns to face the opponent once they've crossed over. Characters only
+    /// turn around on the ground outside of moves and stun, so a cross-up
+    /// jump keeps its direction until landing.
+    fn update_facing(&mut self, opponent: &PlayerGameState) {
+        if !matches!(
+            self.character_state,
+            CharacterState::Idle
+                | CharacterState::Walking { .. }
+                | CharacterState::Crouching
+                | CharacterState::Guarding
+        ) || self.is_airborne()
+        {
+            return;
+        }
+
+        match self.pos_x.cmp(&opponent.pos_x) {
+            Ordering::Less => self.facing = Facing::Right,
+            Ordering::Greater => self.facing = Facing::Left,
+            Ordering::Equal => {}
+        }
+    }
+
     pub fn draw(&self) {
         // TODO: Write this
     }
+
+    /// Counts down hitstop, returning true if the character is frozen this frame.
+    fn tick_freeze_frames(&mut self) -> bool {
+        if self.freeze_frames > 0 {
+            self.freeze_frames -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the segment of the current move being performed, if any.
+    pub fn current_frame_data(&self) -> Option<&'static FrameData> {
+        match &self.character_state {
+            CharacterState::Attacking { attack, frame, .. } => attack
+                .data
+                .get_frame_data(*frame)
+                .map(|(_, frame_data)| frame_data),
+            _ => None,
+        }
+    }
+
+    pub fn hitboxes(&self) -> Option<&'static Hitboxes> {
+        self.current_frame_data()?.hitboxes.as_ref()
+    }
+
+    /// The player's position in the same units as their collision boxes.
+    pub fn position(&self) -> IVec2 {
+        IVec2::new(self.pos_x as i32, self.pos_y as i32)
+    }
+
+    pub fn hurtboxes(&self) -> &'static Hurtboxes {
+        let default = if self.is_crouching() {
+            &self.character.crouching_hurtboxes
+        } else {
+            &self.character.hurtboxes
+        };
+
+        self.current_frame_data()
+            .and_then(|frame_data| frame_data.hurtboxes.as_ref())
+            .unwrap_or(default)
+    }
 }
 
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum MatchResult {
     Player1Win,
     Player2Win,
@@ -35,14 +144,88 @@ pub enum MatchResult {
 }
 
 impl MatchState {
+    pub fn new(
+        player_1: &'static CharacterDefinition,
+        player_2: &'static CharacterDefinition,
+        settings: MatchSettings,
+    ) -> Self {
+        let mut state = Self {
+            settings,
+            phase: RoundPhase::Fight,
+            round: 0,
+            timer: None,
+            player_1: PlayerGameState::new(player_1, -START_POSITION_X, Facing::Right),
+            player_2: PlayerGameState::new(player_2, START_POSITION_X, Facing::Left),
+            projectiles: [None; MAX_PROJECTILES],
+        };
+        state.start_round();
+        state
+    }
+
+    /// Returns the facing of player 1 and player 2, used to convert
+    /// raw left/right inputs into forward/backward.
+    pub fn facings(&self) -> (Facing, Facing) {
+        (self.player_1.facing, self.player_2.facing)
+    }
+
+    /// Advances the match by a single frame, returning the result once
+    /// the match is over.
     pub fn update(&mut self, p1_inputs: InputState, p2_inputs: InputState) -> Option<MatchResult> {
+        // Players can't act once the round has been decided.
+        let (p1_inputs, p2_inputs) = match self.phase {
+            RoundPhase::RoundOver { .. } => (InputState::default(), InputState::default()),
+            _ => (p1_inputs, p2_inputs),
+        };
+
         // Update Player Inputs
         self.player_1.input_buffer.push(p1_inputs);
         self.player_2.input_buffer.push(p2_inputs);
 
-        // TODO: Update the game simulation
+        match self.phase {
+            RoundPhase::Intro(_) => self.update_intro(),
+            RoundPhase::Fight => {
+                self.simulate();
+                self.update_timer();
+                if let Some(result) = self.round_result() {
+                    self.end_round(result);
+                }
+            }
+            RoundPhase::RoundOver { .. } => self.update_round_over(),
+            RoundPhase::MatchOver(_) => {}
+        }
+
+        match self.phase {
+            RoundPhase::MatchOver(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Runs a single frame of gameplay using the inputs already pushed.
+    pub(super) fn simulate(&mut self) {
+        let p1_frozen = self.player_1.tick_freeze_frames();
+        let p2_frozen = self.player_2.tick_freeze_frames();
+
+        if !p1_frozen {
+            self.player_1.update();
+            self.spawn_projectile(Owner::Player1);
+        }
+        if !p2_frozen {
+            self.player_2.update();
+            self.spawn_projectile(Owner::Player2);
+        }
+
+        self.apply_super_flash();
+
+        self.update_physics(p1_frozen, p2_frozen);
+        self.resolve_push_boxes();
 
-        self.update_timer()
+        self.resolve_throws();
+        self.update_proximity_guard();
+        self.resolve_hits();
+        self.update_projectiles(p1_frozen, p2_frozen);
+
+        self.player_1.update_facing(&self.player_2);
+        self.player_2.update_facing(&self.player_1);
     }
 
     pub fn draw(&self) {
@@ -54,27 +237,4 @@ impl MatchState {
 
         // TODO: Draw Background (Or do this elsewhere?)
     }
-
-    fn update_timer(&mut self) -> Option<MatchResult> {
-        if self.timer == 0 {
-            let p1_health_percentage =
-                self.player_1.character_stats.health as f32 / self.player_1.character.max_hp as f32;
-            let p2_health_percentage =
-                self.player_2.character_stats.health as f32 / self.player_2.character.max_hp as f32;
-
-            Some(
-                match p1_health_percentage
-                    .partial_cmp(&p2_health_percentage)
-                    .unwrap()
-                {
-                    Ordering::Less => MatchResult::Player2Win,
-                    Ordering::Equal => MatchResult::Draw,
-                    Ordering::Greater => MatchResult::Player1Win,
-                },
-            )
-        } else {
-            self.timer -= 1;
-            None
-        }
-    }
 }
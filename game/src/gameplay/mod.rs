@@ -1,17 +1,38 @@
+mod blocking;
+mod boost;
+mod cancels;
 mod character_state;
 mod character_stats;
 mod collision_box;
 mod command_list;
+mod facing;
+mod hit_resolution;
 mod input_buffer;
+mod juggle;
 mod match_state;
 mod move_data;
+mod parry;
+mod physics;
+mod projectile;
+mod push_box;
 mod rect;
+mod round;
+mod snapshot;
+mod state_machine;
+mod super_meter;
+#[cfg(test)]
+pub(crate) mod testing;
+mod throws;
 
 pub use character_state::*;
 pub use character_stats::*;
 pub use collision_box::*;
 pub use command_list::*;
+pub use facing::*;
 pub use input_buffer::*;
 pub use match_state::*;
 pub use move_data::*;
+pub use projectile::*;
 pub use rect::*;
+pub use round::*;
+pub use snapshot::*;
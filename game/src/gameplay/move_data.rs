@@ -1,19 +1,88 @@
-use super::{collision_box::CollisionBoxes, command_list::MoveType};
+use glam::IVec2;
+
+use super::{
+    collision_box::CollisionBoxes,
+    command_list::{Move, MoveType},
+};
 
 pub struct MoveData {
-    move_type: MoveType,
-    frame_data: &'static [FrameData],
+    pub move_type: MoveType,
+    /// Super meter gained when the move starts, so it's earned even on whiff.
+    pub whiff_meter_gain: usize,
+    /// Super meter spent to use the move, usually whole stocks for supers.
+    pub meter_cost: usize,
+    /// Frames both players are frozen for when the move starts.
+    pub super_flash_frames: usize,
+    pub frame_data: &'static [FrameData],
 }
 
 impl MoveData {
     pub fn get_total_frames(&self) -> usize {
         self.frame_data.iter().map(|f| f.duration).sum()
     }
+
+    /// Returns true if any segment from the given frame onwards has hitboxes
+    /// which strike, rather than throw.
+    pub fn has_strikes_from(&self, frame: usize) -> bool {
+        let Some((index, _)) = self.get_frame_data(frame) else {
+            return false;
+        };
+
+        self.frame_data[index..].iter().any(|frame_data| {
+            matches!(
+                frame_data.hitboxes,
+                Some(Hitboxes {
+                    attack_data: AttackData::Strike(_),
+                    ..
+                })
+            )
+        })
+    }
+
+    /// Returns the index and data of the segment running on the given frame
+    /// of the move, or None once the move has finished.
+    pub fn get_frame_data(&self, frame: usize) -> Option<(usize, &'static FrameData)> {
+        let mut start = 0;
+        for (index, frame_data) in self.frame_data.iter().enumerate() {
+            start += frame_data.duration;
+            if frame < start {
+                return Some((index, frame_data));
+            }
+        }
+
+        None
+    }
 }
 
+/// What a move can be canceled into once it has hit or been blocked.
+/// Each level also allows everything the levels above it allow.
 pub enum CancelProperties {
     NoCancel,
+    SuperCancel,
     SpecialCancel,
+    /// Normals can also chain into stronger normals, ie. 5a into 5b.
+    ChainCancel,
+}
+
+impl CancelProperties {
+    pub fn allows(&self, current: &Move, next: &Move) -> bool {
+        match next.data.move_type {
+            MoveType::Super => !matches!(self, CancelProperties::NoCancel),
+            MoveType::Special => matches!(
+                self,
+                CancelProperties::SpecialCancel | CancelProperties::ChainCancel
+            ),
+            MoveType::Normal => {
+                matches!(self, CancelProperties::ChainCancel)
+                    && current.data.move_type == MoveType::Normal
+                    && current
+                        .command
+                        .button
+                        .zip(next.command.button)
+                        .is_some_and(|(current, next)| current.chains_into(next))
+            }
+        }
+    }
 }
 
 pub enum AttackData {
@@ -21,38 +90,121 @@ pub enum AttackData {
     Throw(ThrowData),
 }
 
-pub enum ThrowData {
-    // TODO
+/// A throw connects when its hitboxes overlap a grounded opponent's
+/// hurtboxes. It can't be blocked, but the defender can escape by
+/// pressing AS within the tech window.
+#[derive(Hash)]
+pub struct ThrowData {
+    pub damage: usize,
+    /// Frames the throw animation lasts for after connecting.
+    pub duration: usize,
+    pub tech_window: usize,
+    /// Distance in front of the attacker the defender is held at.
+    pub hold_offset: isize,
+    /// Distance in front of the attacker the defender lands at,
+    /// negative for throws which switch sides.
+    pub release_offset: isize,
+    pub knockdown_frames: usize,
 }
 
 pub struct StrikeData {
-    damage: usize,
-    hit_stun: usize,
-    block_stun: usize,
-    block_hitstop_frames: usize,
-    hit_hitstop_frames: usize,
-    cancel_properties: CancelProperties,
-    kit_kind: HitKind,
+    pub damage: usize,
+    pub hit_stun: usize,
+    pub block_stun: usize,
+    pub block_hitstop_frames: usize,
+    pub hit_hitstop_frames: usize,
+    pub cancel_properties: CancelProperties,
+    pub hit_kind: HitKind,
+    /// Velocity the defender is pushed away with, where positive x is away
+    /// from the attacker and negative y is upwards. Only x applies on block.
+    /// Any upwards knockback launches the defender into a juggle.
+    pub knockback: IVec2,
+    /// Juggle points used up by hitting an airborne defender, see `JUGGLE_LIMIT`.
+    pub juggle_cost: usize,
+    /// Whether a juggled defender bounces off the ground instead of landing.
+    pub ground_bounce: bool,
+    /// Whether a juggled defender bounces back off the wall.
+    pub wall_bounce: bool,
+    /// How long a juggled defender stays down once they land.
+    pub knockdown: KnockdownKind,
+    /// Super meter gained by the attacker when the strike hits.
+    pub hit_meter_gain: usize,
+    /// Super meter gained by the attacker when the strike is blocked.
+    pub block_meter_gain: usize,
+    /// Super meter gained by the defender whether they're hit or block.
+    pub defender_meter_gain: usize,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub enum KnockdownKind {
+    /// Getting back up quickly, after the character's `soft_knockdown_frames`.
+    Soft,
+    /// Lying on the ground for the character's `hard_knockdown_frames`.
+    Hard,
 }
 
+/// Where a strike must be blocked. High attacks (overheads) must be blocked
+/// standing, low attacks crouching, and medium attacks either way.
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub enum HitKind {
     High,
     Medium,
     Low,
 }
 
+/// A parry negates the damage of a strike which lands during its window,
+/// as long as it's the right height. Holding down while parrying catches
+/// lows and mediums, otherwise it catches highs and mediums.
+pub struct ParryData {
+    pub hitstop_frames: usize,
+    /// Extra frames the attacker stays frozen for after the hitstop,
+    /// letting the parrying character act first.
+    pub advantage_frames: usize,
+    /// Super meter gained for a successful parry.
+    pub meter_gain: usize,
+}
+
+/// An attack which travels across the stage on its own, such as a fireball.
+pub struct ProjectileData {
+    /// Where the projectile appears relative to the character's feet,
+    /// as if facing right.
+    pub spawn_offset: IVec2,
+    /// Distance travelled forwards each frame.
+    pub speed: isize,
+    /// Frames until the projectile disappears if it hasn't hit anything.
+    pub lifetime: usize,
+    /// How many times the projectile can hit, or clash with another
+    /// projectile, before it disappears.
+    pub hit_count: usize,
+    /// Frames between each hit of a multi-hit projectile.
+    pub hit_interval: usize,
+    pub strike: StrikeData,
+    pub collision: &'static [CollisionBoxes],
+}
+
 pub struct Hitboxes {
-    attack_data: AttackData,
-    collision: &'static [CollisionBoxes],
+    pub attack_data: AttackData,
+    pub collision: &'static [CollisionBoxes],
 }
 
 pub struct Hurtboxes {
-    collision: &'static [CollisionBoxes],
+    pub collision: &'static [CollisionBoxes],
 }
 
+/// A segment of a move, such as its startup, active or recovery frames.
 pub struct FrameData {
-    duration: usize,
-    hitboxes: Option<Hitboxes>,
-    hurtboxes: Option<Hurtboxes>,
-    blocking_volume: &'static [CollisionBoxes],
+    pub duration: usize,
+    pub hitboxes: Option<Hitboxes>,
+    /// Replaces the character's default hurtboxes for this segment.
+    pub hurtboxes: Option<Hurtboxes>,
+    /// Push boxes which stop characters overlapping, replacing the character's
+    /// default push box for this segment unless empty.
+    pub blocking_volume: &'static [CollisionBoxes],
+    /// Strikes landing during this segment are parried instead.
+    pub parry: Option<ParryData>,
+    /// Launched on the first frame of this segment.
+    pub projectile: Option<ProjectileData>,
+    /// Whether the move can be canceled during this segment, once it
+    /// has connected.
+    pub cancellable: bool,
 }
@@ -0,0 +1,172 @@
+use super::{
+    CharacterState, Contact, HitKind, ParryData, PlayerGameState, StrikeData, VerticalInput,
+};
+
+impl PlayerGameState {
+    /// Returns the parry catching the strike, if it lands during a parry
+    /// window and the character is parrying at the right height.
+    pub(super) fn parries(&self, strike: &StrikeData) -> Option<&'static ParryData> {
+        let parry = self.current_frame_data()?.parry.as_ref()?;
+
+        let low =
+            self.input_buffer.get_last_entry().state.direction.vertical == VerticalInput::Down;
+        let caught = match strike.hit_kind {
+            HitKind::High => !low,
+            HitKind::Medium => true,
+            HitKind::Low => low,
+        };
+
+        caught.then_some(parry)
+    }
+
+    /// The parrying character's side of a parry, skipping their whiff recovery.
+    pub(super) fn parry(&mut self, parry: &ParryData) {
+        self.freeze_frames = parry.hitstop_frames;
+        self.gain_meter(parry.meter_gain);
+        self.change_state(CharacterState::Idle);
+    }
+}
+
+/// Negates a strike with a parry. Both characters freeze for the parry's
+/// hitstop, with the attacker frozen a little longer, and the defender
+/// skips their whiff recovery.
+pub(super) fn parry_strike(
+    attacker: &mut PlayerGameState,
+    defender: &mut PlayerGameState,
+    segment: usize,
+    parry: &ParryData,
+) {
+    if let CharacterState::Attacking { contact, .. } = &mut attacker.character_state {
+        *contact = Some(Contact {
+            segment,
+            blocked: true,
+            parried: true,
+        });
+    }
+    attacker.freeze_frames = parry.hitstop_frames + parry.advantage_frames;
+    defender.parry(parry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, MatchState, ParryData,
+        testing::{close_attack, find_move, hold, input, jump_in, new_close_fight},
+    };
+
+    fn parry_data() -> &'static ParryData {
+        find_move("4s").data.frame_data[1].parry.as_ref().unwrap()
+    }
+
+    /// Player 2 parries straight away, then holds `height` through the parry
+    /// window. Returns whether the attack was parried.
+    fn parries(mut state: MatchState, height: &str) -> bool {
+        let max_hp = state.player_2.character.max_hp;
+        let mut parried = false;
+        state.update(input("5"), input("4s"));
+        for _ in 0..30 {
+            state.update(input("5"), input(height));
+            parried |= matches!(
+                state.player_1.character_state,
+                CharacterState::Attacking {
+                    contact: Some(contact),
+                    ..
+                } if contact.parried
+            );
+        }
+
+        let hit = state.player_2.character_stats.health < max_hp;
+        assert!(parried != hit, "the attack should connect exactly once");
+        parried
+    }
+
+    #[test]
+    fn mediums_can_be_parried_high_or_low() {
+        assert!(parries(close_attack("5a"), "5"));
+        assert!(parries(close_attack("5a"), "2"));
+    }
+
+    #[test]
+    fn lows_must_be_parried_low() {
+        assert!(parries(close_attack("2a"), "2"));
+        assert!(!parries(close_attack("2a"), "5"));
+    }
+
+    #[test]
+    fn highs_must_be_parried_high() {
+        assert!(parries(jump_in(), "5"));
+        assert!(!parries(jump_in(), "2"));
+    }
+
+    #[test]
+    fn parries_negate_damage_and_freeze_the_attacker_for_longer() {
+        let parry = parry_data();
+        let mut state = close_attack("5a");
+        state.update(input("5"), input("4s"));
+        let meter = state.player_2.character_stats.super_meter;
+
+        while state.player_2.freeze_frames == 0 {
+            state.update(input("5"), input("5"));
+        }
+
+        assert_eq!(
+            state.player_2.character_stats.health,
+            state.player_2.character.max_hp
+        );
+        assert_eq!(
+            state.player_2.character_stats.super_meter,
+            meter + parry.meter_gain
+        );
+        assert_eq!(state.player_2.freeze_frames, parry.hitstop_frames);
+        assert_eq!(
+            state.player_1.freeze_frames,
+            parry.hitstop_frames + parry.advantage_frames
+        );
+        // The parry skips its whiff recovery.
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Idle
+        ));
+    }
+
+    #[test]
+    fn whiffed_parries_recover_fully() {
+        let mut state = new_close_fight();
+        let total_frames = find_move("4s").data.get_total_frames();
+
+        state.update(input("5"), input("4s"));
+        hold(&mut state, total_frames - 1, "5", "5");
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Attacking { .. }
+        ));
+
+        hold(&mut state, 1, "5", "5");
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Idle
+        ));
+    }
+
+    #[test]
+    fn projectiles_can_be_parried() {
+        let mut state = new_close_fight();
+
+        // The fireball comes out 12 frames later, already touching player 2.
+        state.update(input("2s"), input("5"));
+        hold(&mut state, 9, "5", "5");
+        state.update(input("5"), input("4s"));
+        let meter = state.player_2.character_stats.super_meter;
+        hold(&mut state, 30, "5", "5");
+
+        assert!(state.projectiles.iter().all(Option::is_none));
+        assert_eq!(
+            state.player_2.character_stats.health,
+            state.player_2.character.max_hp
+        );
+        assert_eq!(
+            state.player_2.character_stats.super_meter,
+            meter + parry_data().meter_gain
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use super::{CharacterState, Facing, MatchState, PlayerGameState};
+
+// All positions and velocities are integers in world units, where 1000 units
+// is a metre, so are already finer than a pixel. Keeping them integers means
+// the simulation plays out identically on every machine, for replays and rollback.
+
+/// Distance from the centre of the stage to either wall.
+pub const STAGE_HALF_WIDTH: isize = 4500;
+/// How much grounded velocity, such as pushback, slows by each frame.
+const GROUND_FRICTION: isize = 4;
+
+impl MatchState {
+    /// Moves both characters by their velocities. Pushback which can't move a
+    /// character any further into the corner pushes their opponent away instead.
+    pub(super) fn update_physics(&mut self, p1_frozen: bool, p2_frozen: bool) {
+        let (p1_facing, p2_facing) = self.facings();
+        let p1_blocked = if p1_frozen {
+            0
+        } else {
+            self.player_1.apply_velocity(p1_facing)
+        };
+        let p2_blocked = if p2_frozen {
+            0
+        } else {
+            self.player_2.apply_velocity(p2_facing)
+        };
+
+        self.player_2.pushback_x -= p1_blocked;
+        self.player_1.pushback_x -= p2_blocked;
+    }
+}
+
+impl PlayerGameState {
+    pub fn is_airborne(&self) -> bool {
+        self.pos_y < 0 || self.vel_y < 0
+    }
+
+    /// Integrates a single frame of movement, applying gravity in the air and
+    /// friction on the ground. Pushback from the opponent slows in the air as
+    /// well as on the ground, even while walking, as it would otherwise keep
+    /// going forever. Returns the velocity of any pushback which was stopped
+    /// by a wall while in stun, so it can be passed on to the opponent.
+    fn apply_velocity(&mut self, facing: Facing) -> isize {
+        let airborne = self.is_airborne();
+        self.pos_x += self.vel_x + self.pushback_x;
+        self.pos_y += self.vel_y;
+        self.pushback_x -= self.pushback_x.signum() * GROUND_FRICTION.min(self.pushback_x.abs());
+
+        if airborne {
+            self.vel_y += self.character.gravity;
+            if self.pos_y >= 0 {
+                self.land();
+            }
+        } else if !matches!(
+            self.character_state,
+            CharacterState::Walking { .. }
+                | CharacterState::Dashing { .. }
+                | CharacterState::Boosting(_)
+        ) {
+            self.vel_x -= self.vel_x.signum() * GROUND_FRICTION.min(self.vel_x.abs());
+        }
+
+        if !self.keep_inside_walls(facing) {
+            return 0;
+        }
+
+        let blocked = self.vel_x;
+        self.vel_x = 0;
+        self.pushback_x = 0;
+
+        if self.bounce_off_wall(blocked) {
+            return 0;
+        }
+
+        if matches!(
+            self.character_state,
+            CharacterState::Hitstun(_)
+                | CharacterState::Blockstun(_)
+                | CharacterState::Juggle { .. }
+        ) {
+            blocked
+        } else {
+            0
+        }
+    }
+
+    /// Moves the character back inside the stage if their push box has gone
+    /// past either wall, returning true if they were moved.
+    fn keep_inside_walls(&mut self, facing: Facing) -> bool {
+        let Some(bounds) = self.push_box(facing) else {
+            return false;
+        };
+
+        let left = bounds.top_left.x as isize;
+        let right = bounds.bottom_right.x as isize;
+        let correction = if left < -STAGE_HALF_WIDTH {
+            -STAGE_HALF_WIDTH - left
+        } else if right > STAGE_HALF_WIDTH {
+            STAGE_HALF_WIDTH - right
+        } else {
+            0
+        };
+
+        self.pos_x += correction;
+        correction != 0
+    }
+
+    fn land(&mut self) {
+        self.pos_y = 0;
+        if matches!(self.character_state, CharacterState::Juggle { .. }) {
+            self.land_from_juggle();
+            return;
+        }
+
+        self.vel_x = 0;
+        self.vel_y = 0;
+
+        match self.character_state {
+            CharacterState::Jumping => {
+                self.change_state(CharacterState::Landing(self.character.landing_frames))
+            }
+            CharacterState::Attacking { attack, .. } if attack.command.airborne => {
+                self.change_state(CharacterState::Landing(self.character.landing_frames))
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState,
+        testing::{input, new_fight},
+    };
+    use super::{GROUND_FRICTION, STAGE_HALF_WIDTH};
+
+    #[test]
+    fn pushback_off_the_wall_wears_off_while_walking() {
+        let mut state = new_fight();
+        state.player_1.pos_x = -STAGE_HALF_WIDTH;
+        state.player_1.vel_x = -40;
+        state.player_1.character_state = CharacterState::Hitstun(60);
+        state.player_2.pos_x = -3000;
+
+        // Player 1 can't be pushed into the wall, so player 2 is pushed back
+        // from the next frame, despite walking forwards.
+        state.update(input("5"), input("6"));
+        assert_eq!(state.player_2.pushback_x, 40 - GROUND_FRICTION);
+        let pos_x = state.player_2.pos_x;
+        state.update(input("5"), input("6"));
+        assert!(state.player_2.pos_x > pos_x);
+
+        for _ in 0..20 {
+            state.update(input("5"), input("6"));
+        }
+        assert_eq!(state.player_2.pushback_x, 0);
+
+        // Player 2 walks forwards at their normal speed again.
+        let walk_speed = state.player_2.character.forward_walk_speed;
+        for _ in 0..20 {
+            let pos_x = state.player_2.pos_x;
+            state.update(input("5"), input("6"));
+            assert_eq!(state.player_2.pos_x, pos_x - walk_speed);
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use glam::IVec2;
+
+use super::{
+    CharacterState, Facing, MatchState, PlayerGameState, ProjectileData, physics::STAGE_HALF_WIDTH,
+};
+
+/// The most projectiles which can be on screen at once. Any more aren't spawned.
+pub const MAX_PROJECTILES: usize = 8;
+/// How far past a wall a projectile can travel before it's removed.
+const OFFSTAGE_MARGIN: isize = 1000;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Owner {
+    Player1,
+    Player2,
+}
+
+#[derive(Clone, Copy)]
+pub struct Projectile {
+    pub owner: Owner,
+    pub data: &'static ProjectileData,
+    pub pos_x: isize,
+    pub pos_y: isize,
+    pub vel_x: isize,
+    /// The owner's facing when the projectile was spawned.
+    pub facing: Facing,
+    pub lifetime: usize,
+    pub hits_left: usize,
+    /// Frames until the projectile can hit again.
+    pub cooldown: usize,
+}
+
+impl Projectile {
+    fn position(&self) -> IVec2 {
+        IVec2::new(self.pos_x as i32, self.pos_y as i32)
+    }
+
+    fn collides(&self, other: &Projectile) -> bool {
+        self.data.collision.iter().any(|boxes| {
+            other.data.collision.iter().any(|other_boxes| {
+                boxes.collides_at(
+                    self.position(),
+                    self.facing,
+                    other_boxes,
+                    other.position(),
+                    other.facing,
+                )
+            })
+        })
+    }
+
+    fn hits(&self, player: &PlayerGameState) -> bool {
+        let hurtboxes = player.hurtboxes();
+        self.data.collision.iter().any(|boxes| {
+            hurtboxes.collision.iter().any(|hurtbox| {
+                boxes.collides_at(
+                    self.position(),
+                    self.facing,
+                    hurtbox,
+                    player.position(),
+                    player.facing,
+                )
+            })
+        })
+    }
+}
+
+/// Uses up one of the projectile's hits, removing it once it has none left.
+fn spend_hit(slot: &mut Option<Projectile>) {
+    let Some(projectile) = slot else {
+        return;
+    };
+
+    projectile.hits_left = projectile.hits_left.saturating_sub(1);
+    projectile.cooldown = projectile.data.hit_interval;
+    if projectile.hits_left == 0 {
+        *slot = None;
+    }
+}
+
+impl MatchState {
+    /// Spawns the projectile of the owner's current move if they've just
+    /// reached the segment which launches it, as long as there's room.
+    pub(super) fn spawn_projectile(&mut self, owner: Owner) {
+        let player = match owner {
+            Owner::Player1 => &self.player_1,
+            Owner::Player2 => &self.player_2,
+        };
+        let Some(data) = player.projectile_to_spawn() else {
+            return;
+        };
+        let Some(slot) = self.projectiles.iter_mut().find(|slot| slot.is_none()) else {
+            return;
+        };
+
+        let sign = player.facing.sign();
+        *slot = Some(Projectile {
+            owner,
+            data,
+            pos_x: player.pos_x + sign * data.spawn_offset.x as isize,
+            pos_y: player.pos_y + data.spawn_offset.y as isize,
+            vel_x: sign * data.speed,
+            facing: player.facing,
+            lifetime: data.lifetime,
+            hits_left: data.hit_count,
+            cooldown: 0,
+        });
+    }
+
+    /// Moves each projectile, removing those which have expired or left the
+    /// stage, then resolves clashes between projectiles and hits on players.
+    /// Projectiles are frozen along with their owner during hitstop and super
+    /// flash, though they can still clash and hit.
+    pub(super) fn update_projectiles(&mut self, p1_frozen: bool, p2_frozen: bool) {
+        for slot in &mut self.projectiles {
+            let Some(projectile) = slot else {
+                continue;
+            };
+            let frozen = match projectile.owner {
+                Owner::Player1 => p1_frozen,
+                Owner::Player2 => p2_frozen,
+            };
+            if frozen {
+                continue;
+            }
+
+            projectile.pos_x += projectile.vel_x;
+            projectile.lifetime = projectile.lifetime.saturating_sub(1);
+            projectile.cooldown = projectile.cooldown.saturating_sub(1);
+            if projectile.lifetime == 0
+                || projectile.pos_x.abs() > STAGE_HALF_WIDTH + OFFSTAGE_MARGIN
+            {
+                *slot = None;
+            }
+        }
+
+        self.clash_projectiles();
+        for index in 0..MAX_PROJECTILES {
+            self.resolve_projectile_hit(index);
+        }
+    }
+
+    /// Opposing projectiles which touch each use up a hit, so equal
+    /// fireballs cancel each other out.
+    fn clash_projectiles(&mut self) {
+        for i in 0..MAX_PROJECTILES {
+            for j in i + 1..MAX_PROJECTILES {
+                let (Some(a), Some(b)) = (&self.projectiles[i], &self.projectiles[j]) else {
+                    continue;
+                };
+                if a.owner == b.owner || a.cooldown > 0 || b.cooldown > 0 || !a.collides(b) {
+                    continue;
+                }
+
+                spend_hit(&mut self.projectiles[i]);
+                spend_hit(&mut self.projectiles[j]);
+            }
+        }
+    }
+
+    fn resolve_projectile_hit(&mut self, index: usize) {
+        let Some(projectile) = self.projectiles[index] else {
+            return;
+        };
+        let (owner, defender) = match projectile.owner {
+            Owner::Player1 => (&mut self.player_1, &mut self.player_2),
+            Owner::Player2 => (&mut self.player_2, &mut self.player_1),
+        };
+
+        let strike = &projectile.data.strike;
+        if projectile.cooldown > 0
+            || !defender.character_state.can_be_hit()
+            || !defender.can_be_juggled_by(strike)
+            || !projectile.hits(defender)
+        {
+            return;
+        }
+
+        if let Some(parry) = defender.parries(strike) {
+            defender.parry(parry);
+        } else {
+            let blocked = defender.blocks(strike);
+            owner.gain_meter(if blocked {
+                strike.block_meter_gain
+            } else {
+                strike.hit_meter_gain
+            });
+            defender.receive_strike(projectile.facing, strike, blocked);
+        }
+
+        spend_hit(&mut self.projectiles[index]);
+    }
+}
+
+impl PlayerGameState {
+    /// The projectile launched by the current move this frame, if the move
+    /// has just entered a segment which launches one.
+    fn projectile_to_spawn(&self) -> Option<&'static ProjectileData> {
+        let CharacterState::Attacking { attack, frame, .. } = &self.character_state else {
+            return None;
+        };
+
+        let (index, frame_data) = attack.data.get_frame_data(*frame)?;
+        let entered = *frame == 0
+            || attack
+                .data
+                .get_frame_data(*frame - 1)
+                .is_some_and(|(previous, _)| previous != index);
+
+        frame_data.projectile.as_ref().filter(|_| entered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::super::{
+        CancelProperties, CharacterState, CollisionBoxes, Facing, HitKind, KnockdownKind,
+        MatchState, ProjectileData, Rect, StrikeData,
+        physics::STAGE_HALF_WIDTH,
+        testing::{find_move, hold, input, new_close_fight, new_fight},
+    };
+    use super::{MAX_PROJECTILES, OFFSTAGE_MARGIN, Owner, Projectile};
+
+    /// A fireball which can hit twice, 10 frames apart.
+    static TWO_HIT_FIREBALL: ProjectileData = ProjectileData {
+        spawn_offset: IVec2::new(500, 0),
+        speed: 0,
+        lifetime: 180,
+        hit_count: 2,
+        hit_interval: 10,
+        strike: StrikeData {
+            damage: 10,
+            hit_stun: 30,
+            block_stun: 30,
+            block_hitstop_frames: 0,
+            hit_hitstop_frames: 0,
+            cancel_properties: CancelProperties::NoCancel,
+            hit_kind: HitKind::Medium,
+            knockback: IVec2::ZERO,
+            juggle_cost: 0,
+            ground_bounce: false,
+            wall_bounce: false,
+            knockdown: KnockdownKind::Soft,
+            hit_meter_gain: 0,
+            block_meter_gain: 0,
+            defender_meter_gain: 0,
+        },
+        collision: &[CollisionBoxes {
+            data: &[Rect::new(-200, -1300, 200, -900)],
+        }],
+    };
+
+    fn fireball(notation: &'static str) -> &'static ProjectileData {
+        find_move(notation).data.frame_data[1]
+            .projectile
+            .as_ref()
+            .unwrap()
+    }
+
+    /// A projectile placed directly into the match, rather than thrown.
+    fn projectile(owner: Owner, data: &'static ProjectileData, pos_x: isize) -> Projectile {
+        let facing = match owner {
+            Owner::Player1 => Facing::Right,
+            Owner::Player2 => Facing::Left,
+        };
+        Projectile {
+            owner,
+            data,
+            pos_x,
+            pos_y: 0,
+            vel_x: facing.sign() * data.speed,
+            facing,
+            lifetime: data.lifetime,
+            hits_left: data.hit_count,
+            cooldown: 0,
+        }
+    }
+
+    fn count(state: &MatchState) -> usize {
+        state.projectiles.iter().flatten().count()
+    }
+
+    #[test]
+    fn projectiles_spawn_in_front_of_their_owner_heading_their_way() {
+        let mut state = new_fight();
+        while count(&state) < 2 {
+            hold(&mut state, 1, "2s", "2s");
+        }
+
+        for (projectile, player) in state
+            .projectiles
+            .iter()
+            .flatten()
+            .zip([&state.player_1, &state.player_2])
+        {
+            let sign = player.facing.sign();
+            assert!(projectile.facing == player.facing);
+            assert_eq!(projectile.vel_x, sign * fireball("2s").speed);
+            // It has already moved once on the frame it was spawned.
+            assert_eq!(
+                projectile.pos_x - projectile.vel_x,
+                player.pos_x + sign * fireball("2s").spawn_offset.x as isize
+            );
+        }
+    }
+
+    #[test]
+    fn projectiles_expire_at_the_end_of_their_lifetime() {
+        let mut state = new_fight();
+        let mut slow = projectile(Owner::Player1, fireball("1s"), 0);
+        slow.lifetime = 10;
+        state.projectiles[0] = Some(slow);
+
+        hold(&mut state, 9, "5", "5");
+        assert_eq!(count(&state), 1);
+        hold(&mut state, 1, "5", "5");
+        assert_eq!(count(&state), 0);
+    }
+
+    #[test]
+    fn projectiles_are_removed_once_they_leave_the_stage() {
+        let mut state = new_fight();
+        let edge = STAGE_HALF_WIDTH + OFFSTAGE_MARGIN;
+        state.projectiles[0] = Some(projectile(Owner::Player1, fireball("2s"), edge - 10));
+        state.projectiles[1] = Some(projectile(Owner::Player2, fireball("2s"), edge - 10));
+
+        hold(&mut state, 1, "5", "5");
+        assert!(state.projectiles[0].is_none());
+        assert!(state.projectiles[1].is_some());
+    }
+
+    #[test]
+    fn opposing_projectiles_clash() {
+        let mut state = new_fight();
+        state.projectiles[0] = Some(projectile(Owner::Player1, fireball("2s"), 0));
+        state.projectiles[1] = Some(projectile(Owner::Player2, fireball("2s"), 100));
+        state.projectiles[2] = Some(projectile(Owner::Player1, &TWO_HIT_FIREBALL, -2000));
+        state.projectiles[3] = Some(projectile(Owner::Player2, fireball("2s"), -2000));
+        state.projectiles[4] = Some(projectile(Owner::Player1, fireball("2s"), 2000));
+        state.projectiles[5] = Some(projectile(Owner::Player1, fireball("2s"), 2000));
+
+        hold(&mut state, 1, "5", "5");
+        assert!(state.projectiles[0].is_none());
+        assert!(state.projectiles[1].is_none());
+        // A projectile with more hits survives the clash.
+        assert!(state.projectiles[2].is_some_and(|projectile| projectile.hits_left == 1));
+        assert!(state.projectiles[3].is_none());
+        // A player's own projectiles pass through each other.
+        assert!(state.projectiles[4].is_some());
+        assert!(state.projectiles[5].is_some());
+    }
+
+    #[test]
+    fn projectiles_are_frozen_along_with_their_owner() {
+        let mut state = new_fight();
+        state.projectiles[0] = Some(projectile(Owner::Player1, fireball("2s"), 0));
+        state.projectiles[1] = Some(projectile(Owner::Player2, fireball("2s"), 2000));
+        state.player_1.freeze_frames = 10;
+
+        hold(&mut state, 5, "5", "5");
+        let frozen = state.projectiles[0].unwrap();
+        assert_eq!(frozen.pos_x, 0);
+        assert_eq!(frozen.lifetime, fireball("2s").lifetime);
+        assert_eq!(
+            state.projectiles[1].unwrap().pos_x,
+            2000 - 5 * fireball("2s").speed
+        );
+    }
+
+    #[test]
+    fn no_more_than_max_projectiles_are_spawned() {
+        let mut state = new_fight();
+        for slot in &mut state.projectiles {
+            let mut parked = projectile(Owner::Player2, &TWO_HIT_FIREBALL, 0);
+            parked.pos_y = -5000;
+            *slot = Some(parked);
+        }
+
+        state.update(input("2s"), input("5"));
+        hold(&mut state, 20, "5", "5");
+        assert_eq!(count(&state), MAX_PROJECTILES);
+        assert!(
+            state
+                .projectiles
+                .iter()
+                .flatten()
+                .all(|p| p.owner == Owner::Player2)
+        );
+    }
+
+    #[test]
+    fn multi_hit_projectiles_wait_between_hits() {
+        let mut state = new_close_fight();
+        let max_hp = state.player_2.character.max_hp;
+        let damage = TWO_HIT_FIREBALL.strike.damage;
+        let pos_x = state.player_2.pos_x;
+        state.projectiles[0] = Some(projectile(Owner::Player1, &TWO_HIT_FIREBALL, pos_x));
+
+        hold(&mut state, 1, "5", "5");
+        assert_eq!(state.player_2.character_stats.health, max_hp - damage);
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Hitstun(_)
+        ));
+
+        hold(&mut state, TWO_HIT_FIREBALL.hit_interval - 1, "5", "5");
+        assert_eq!(state.player_2.character_stats.health, max_hp - damage);
+        hold(&mut state, 1, "5", "5");
+        assert_eq!(state.player_2.character_stats.health, max_hp - 2 * damage);
+        assert_eq!(count(&state), 0);
+    }
+}
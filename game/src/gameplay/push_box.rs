@@ -0,0 +1,170 @@
+use std::cmp::Ordering;
+
+use super::{CharacterState, Facing, MatchState, PlayerGameState, Rect, physics::STAGE_HALF_WIDTH};
+
+impl MatchState {
+    /// Pushes overlapping characters apart by half the overlap each, so they
+    /// can't walk through each other. Whoever's centre is further left ends up
+    /// on the left, so a jump which lands past the opponent's centre crosses
+    /// them up. Neither is pushed through a wall, with the other character
+    /// taking the rest of the push instead.
+    pub(super) fn resolve_push_boxes(&mut self) {
+        let (p1_facing, p2_facing) = self.facings();
+        let (Some(p1_box), Some(p2_box)) = (
+            self.player_1.push_box(p1_facing),
+            self.player_2.push_box(p2_facing),
+        ) else {
+            return;
+        };
+
+        if !p1_box.collides(&p2_box) {
+            return;
+        }
+
+        let p1_on_left = match self.player_1.pos_x.cmp(&self.player_2.pos_x) {
+            Ordering::Less => true,
+            Ordering::Greater => false,
+            Ordering::Equal => p1_facing == Facing::Right,
+        };
+
+        let (left, left_box, right, right_box) = if p1_on_left {
+            (&mut self.player_1, p1_box, &mut self.player_2, p2_box)
+        } else {
+            (&mut self.player_2, p2_box, &mut self.player_1, p1_box)
+        };
+
+        let overlap = (left_box.bottom_right.x - right_box.top_left.x) as isize;
+        let mut left_shift = -(overlap / 2);
+        let mut right_shift = overlap + left_shift;
+
+        let left_edge = left_box.top_left.x as isize + left_shift;
+        if left_edge < -STAGE_HALF_WIDTH {
+            let correction = -STAGE_HALF_WIDTH - left_edge;
+            left_shift += correction;
+            right_shift += correction;
+        }
+
+        let right_edge = right_box.bottom_right.x as isize + right_shift;
+        if right_edge > STAGE_HALF_WIDTH {
+            let correction = right_edge - STAGE_HALF_WIDTH;
+            left_shift -= correction;
+            right_shift -= correction;
+        }
+
+        left.pos_x += left_shift;
+        right.pos_x += right_shift;
+    }
+}
+
+impl PlayerGameState {
+    /// The bounds of the character's push box in the world, or None during
+    /// throws, where the throw positions both characters instead.
+    pub fn push_box(&self, facing: Facing) -> Option<Rect> {
+        if matches!(
+            self.character_state,
+            CharacterState::Throwing { .. } | CharacterState::Thrown { .. }
+        ) {
+            return None;
+        }
+
+        let default = if self.is_airborne() {
+            self.character.airborne_push_box
+        } else {
+            self.character.push_box
+        };
+
+        let volume = self
+            .current_frame_data()
+            .map(|frame_data| frame_data.blocking_volume)
+            .filter(|volume| !volume.is_empty())
+            .unwrap_or(default);
+
+        volume
+            .iter()
+            .flat_map(|boxes| boxes.data)
+            .map(|rect| rect.placed(self.position(), facing))
+            .reduce(|bounds, rect| bounds.union(&rect))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, Facing, MatchState,
+        physics::STAGE_HALF_WIDTH,
+        testing::{hold, new_fight},
+    };
+
+    /// Places the players and lets a frame play out with neither moving.
+    fn place(player_1: isize, player_2: isize) -> MatchState {
+        let mut state = new_fight();
+        state.player_1.pos_x = player_1;
+        state.player_2.pos_x = player_2;
+        hold(&mut state, 1, "5", "5");
+        state
+    }
+
+    #[test]
+    fn overlapping_characters_are_pushed_apart_evenly() {
+        let state = place(-100, 100);
+        assert_eq!(state.player_1.pos_x, -200);
+        assert_eq!(state.player_2.pos_x, 200);
+    }
+
+    #[test]
+    fn characters_are_never_pushed_through_a_wall() {
+        let wall = -STAGE_HALF_WIDTH + 200;
+        let state = place(wall, wall + 200);
+        assert_eq!(state.player_1.pos_x, wall);
+        assert_eq!(state.player_2.pos_x, wall + 400);
+    }
+
+    #[test]
+    fn walking_into_a_cornered_opponent_goes_no_further() {
+        let corner = STAGE_HALF_WIDTH - 200;
+        let mut state = place(corner - 400, corner);
+        hold(&mut state, 30, "6", "5");
+        assert_eq!(state.player_1.pos_x, corner - 400);
+        assert_eq!(state.player_2.pos_x, corner);
+    }
+
+    #[test]
+    fn jumps_landing_past_the_opponents_centre_cross_up() {
+        let mut state = new_fight();
+        state.player_2.pos_x = 300;
+        state.player_1.pos_x = 350;
+        state.player_1.pos_y = -800;
+        state.player_1.character_state = CharacterState::Jumping;
+
+        hold(&mut state, 1, "5", "5");
+        assert!(state.player_1.pos_x > state.player_2.pos_x);
+        let (p1_facing, p2_facing) = state.facings();
+        assert!(
+            !state
+                .player_1
+                .push_box(p1_facing)
+                .unwrap()
+                .collides(&state.player_2.push_box(p2_facing).unwrap())
+        );
+    }
+
+    #[test]
+    fn crossed_up_characters_turn_around_once_grounded() {
+        let mut state = new_fight();
+        state.player_2.pos_x = 300;
+        state.player_1.pos_x = 350;
+        state.player_1.pos_y = -800;
+        state.player_1.character_state = CharacterState::Jumping;
+
+        hold(&mut state, 1, "5", "5");
+        assert!(state.player_1.facing == Facing::Right);
+        assert!(state.player_2.facing == Facing::Right);
+
+        while state.player_1.is_airborne() {
+            assert!(state.player_1.facing == Facing::Right);
+            hold(&mut state, 1, "5", "5");
+        }
+        hold(&mut state, 10, "5", "5");
+        assert!(state.player_1.facing == Facing::Left);
+    }
+}
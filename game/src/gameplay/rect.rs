@@ -1,11 +1,36 @@
 use glam::IVec2;
 
+use super::Facing;
+
+/// An axis aligned box, with y increasing downwards.
+#[derive(Clone, Copy)]
 pub struct Rect {
     pub top_left: IVec2,
     pub bottom_right: IVec2,
 }
 
 impl Rect {
+    pub const fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            top_left: IVec2::new(left, top),
+            bottom_right: IVec2::new(right, bottom),
+        }
+    }
+
+    /// Returns this rect, defined as if facing right, moved to `position`
+    /// and mirrored horizontally when facing left.
+    pub fn placed(&self, position: IVec2, facing: Facing) -> Rect {
+        let (left, right) = match facing {
+            Facing::Right => (self.top_left.x, self.bottom_right.x),
+            Facing::Left => (-self.bottom_right.x, -self.top_left.x),
+        };
+
+        Rect {
+            top_left: IVec2::new(left, self.top_left.y) + position,
+            bottom_right: IVec2::new(right, self.bottom_right.y) + position,
+        }
+    }
+
     pub fn collides(&self, other: &Rect) -> bool {
         let horizontal_overlap =
             self.top_left.x < other.bottom_right.x && self.bottom_right.x > other.top_left.x;
@@ -15,4 +40,12 @@ impl Rect {
 
         horizontal_overlap && vertical_overlap
     }
+
+    /// The smallest rect containing both rects.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            top_left: self.top_left.min(other.top_left),
+            bottom_right: self.bottom_right.max(other.bottom_right),
+        }
+    }
 }
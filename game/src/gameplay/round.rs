@@ -0,0 +1,336 @@
+use std::cmp::Ordering;
+
+use super::{
+    Facing, MAX_PROJECTILES, MatchResult, MatchState, PlayerGameState,
+    match_state::START_POSITION_X,
+};
+
+const ROUND_INTRO_FRAMES: usize = 90;
+/// Frames from the end of a round until the next one starts.
+const ROUND_OVER_FRAMES: usize = 180;
+/// Frames at the start of the round over phase which play at half speed.
+const ROUND_OVER_SLOWDOWN_FRAMES: usize = 60;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct MatchSettings {
+    /// Rounds needed to win the match, ie. 2 for best of 3.
+    pub rounds_to_win: usize,
+    /// Length of each round on the clock, or None for no time limit.
+    pub round_seconds: Option<usize>,
+    /// The rate the match is updated at, for converting seconds to frames.
+    pub frames_per_second: usize,
+}
+
+impl MatchSettings {
+    /// Frames in each round, or None for no time limit.
+    pub fn round_frames(&self) -> Option<usize> {
+        self.round_seconds
+            .map(|seconds| seconds.saturating_mul(self.frames_per_second))
+    }
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        Self {
+            rounds_to_win: 2,
+            round_seconds: Some(99),
+            frames_per_second: 60,
+        }
+    }
+}
+
+#[derive(Hash, Clone, Copy)]
+pub enum RoundPhase {
+    /// Counting down the frames until the fight starts, with players frozen.
+    Intro(usize),
+    Fight,
+    /// The round has been decided, counting down the frames until the next
+    /// round starts. Players can't act, but finish falling and landing.
+    RoundOver {
+        result: MatchResult,
+        frames: usize,
+    },
+    MatchOver(MatchResult),
+}
+
+impl MatchState {
+    /// Decides the round once either player is KO'd or time runs out. Both
+    /// being KO'd on the same frame is a draw, and on time over the player
+    /// with more of their health left wins.
+    pub(super) fn round_result(&self) -> Option<MatchResult> {
+        let p1_ko = self.player_1.character_stats.health == 0;
+        let p2_ko = self.player_2.character_stats.health == 0;
+
+        match (p1_ko, p2_ko) {
+            (true, true) => Some(MatchResult::Draw),
+            (true, false) => Some(MatchResult::Player2Win),
+            (false, true) => Some(MatchResult::Player1Win),
+            (false, false) if self.timer == Some(0) => Some(self.time_over_result()),
+            (false, false) => None,
+        }
+    }
+
+    fn time_over_result(&self) -> MatchResult {
+        // Compares health as a fraction of max health, without dividing.
+        let p1_health = self.player_1.character_stats.health * self.player_2.character.max_hp;
+        let p2_health = self.player_2.character_stats.health * self.player_1.character.max_hp;
+
+        match p1_health.cmp(&p2_health) {
+            Ordering::Less => MatchResult::Player2Win,
+            Ordering::Equal => MatchResult::Draw,
+            Ordering::Greater => MatchResult::Player1Win,
+        }
+    }
+
+    /// Awards the round to the winner, or to both players on a draw.
+    pub(super) fn end_round(&mut self, result: MatchResult) {
+        if result != MatchResult::Player2Win {
+            self.player_1.win_count += 1;
+        }
+        if result != MatchResult::Player1Win {
+            self.player_2.win_count += 1;
+        }
+
+        self.phase = RoundPhase::RoundOver {
+            result,
+            frames: ROUND_OVER_FRAMES,
+        };
+    }
+
+    /// Counts down the round clock, which stops while either player is
+    /// frozen by hitstop or a super flash.
+    pub(super) fn update_timer(&mut self) {
+        if self.player_1.freeze_frames > 0 || self.player_2.freeze_frames > 0 {
+            return;
+        }
+
+        if let Some(timer) = &mut self.timer {
+            *timer = timer.saturating_sub(1);
+        }
+    }
+
+    pub(super) fn update_intro(&mut self) {
+        if let RoundPhase::Intro(frames) = &mut self.phase {
+            *frames = frames.saturating_sub(1);
+            if *frames == 0 {
+                self.phase = RoundPhase::Fight;
+            }
+        }
+    }
+
+    /// Plays out the end of the round, slowed down at first, and then moves
+    /// on to the next round or ends the match.
+    pub(super) fn update_round_over(&mut self) {
+        let RoundPhase::RoundOver { result, frames } = self.phase else {
+            return;
+        };
+
+        let slowed_down = frames + ROUND_OVER_SLOWDOWN_FRAMES > ROUND_OVER_FRAMES;
+        if !slowed_down || frames % 2 == 0 {
+            self.simulate();
+        }
+
+        if frames > 1 {
+            self.phase = RoundPhase::RoundOver {
+                result,
+                frames: frames - 1,
+            };
+            return;
+        }
+
+        let rounds_to_win = self.settings.rounds_to_win;
+        let p1_won = self.player_1.win_count >= rounds_to_win;
+        let p2_won = self.player_2.win_count >= rounds_to_win;
+        match (p1_won, p2_won) {
+            (true, true) => self.phase = RoundPhase::MatchOver(MatchResult::Draw),
+            (true, false) => self.phase = RoundPhase::MatchOver(MatchResult::Player1Win),
+            (false, true) => self.phase = RoundPhase::MatchOver(MatchResult::Player2Win),
+            (false, false) => self.start_round(),
+        }
+    }
+
+    /// Puts both players back at their starting positions with full health
+    /// and fresh meters, ready for the next round's intro.
+    pub(super) fn start_round(&mut self) {
+        self.round += 1;
+        self.timer = self.settings.round_frames();
+        self.player_1.reset(-START_POSITION_X, Facing::Right);
+        self.player_2.reset(START_POSITION_X, Facing::Left);
+        self.projectiles = [None; MAX_PROJECTILES];
+        self.phase = RoundPhase::Intro(ROUND_INTRO_FRAMES);
+    }
+}
+
+impl PlayerGameState {
+    /// Resets everything apart from the rounds won.
+    fn reset(&mut self, pos_x: isize, facing: Facing) {
+        *self = Self {
+            win_count: self.win_count,
+            ..Self::new(self.character, pos_x, facing)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        MatchResult, MatchSettings, MatchState, RoundPhase,
+        testing::{hold, input, new_close_fight, new_fight},
+    };
+
+    /// Puts the players next to each other at the start of a fight, with
+    /// `p1_health` and `p2_health` left.
+    fn close_fight(state: &mut MatchState, p1_health: usize, p2_health: usize) {
+        let fresh = new_close_fight();
+        state.phase = RoundPhase::Fight;
+        state.player_1.pos_x = fresh.player_1.pos_x;
+        state.player_2.pos_x = fresh.player_2.pos_x;
+        state.player_1.character_stats.health = p1_health;
+        state.player_2.character_stats.health = p2_health;
+    }
+
+    /// Plays until the round is decided, returning its result.
+    fn finish_round(state: &mut MatchState, player_1: &str, player_2: &str) -> MatchResult {
+        state.update(input(player_1), input(player_2));
+        for _ in 0..60 {
+            if let RoundPhase::RoundOver { result, .. } = state.phase {
+                return result;
+            }
+            state.update(input("5"), input("5"));
+        }
+        panic!("the round was never decided");
+    }
+
+    /// Plays out the end of a round, returning the match result if it was
+    /// the last one.
+    fn finish_round_over(state: &mut MatchState) -> Option<MatchResult> {
+        let mut result = None;
+        while let RoundPhase::RoundOver { .. } = state.phase {
+            result = state.update(input("5"), input("5"));
+        }
+        result
+    }
+
+    /// Player 1 KOs player 2 with a single hit, or the other way around.
+    fn ko(state: &mut MatchState, p1_wins: bool) -> Option<MatchResult> {
+        let (p1_health, p2_health, winner) = if p1_wins {
+            (1000, 1, MatchResult::Player1Win)
+        } else {
+            (1, 1000, MatchResult::Player2Win)
+        };
+        close_fight(state, p1_health, p2_health);
+        let (player_1, player_2) = if p1_wins { ("5c", "5") } else { ("5", "5c") };
+        assert!(finish_round(state, player_1, player_2) == winner);
+        finish_round_over(state)
+    }
+
+    #[test]
+    fn matches_last_until_a_player_wins_enough_rounds() {
+        let mut state = new_close_fight();
+        state.settings.rounds_to_win = 3;
+
+        assert!(ko(&mut state, true).is_none());
+        assert!(ko(&mut state, false).is_none());
+        assert!(ko(&mut state, true).is_none());
+        assert!(matches!(state.phase, RoundPhase::Intro(_)));
+        assert_eq!(state.round, 4);
+        assert_eq!(state.player_1.win_count, 2);
+        assert_eq!(state.player_2.win_count, 1);
+
+        assert!(ko(&mut state, true) == Some(MatchResult::Player1Win));
+        assert!(matches!(
+            state.phase,
+            RoundPhase::MatchOver(MatchResult::Player1Win)
+        ));
+        assert_eq!(state.player_1.win_count, 3);
+    }
+
+    #[test]
+    fn double_kos_are_draws_which_count_for_both_players() {
+        let mut state = new_close_fight();
+        close_fight(&mut state, 1, 1);
+        assert!(finish_round(&mut state, "5c", "5c") == MatchResult::Draw);
+        assert!(finish_round_over(&mut state).is_none());
+        assert_eq!(state.player_1.win_count, 1);
+        assert_eq!(state.player_2.win_count, 1);
+
+        // With both players on match point, another double KO draws the match.
+        close_fight(&mut state, 1, 1);
+        assert!(finish_round(&mut state, "5c", "5c") == MatchResult::Draw);
+        assert!(finish_round_over(&mut state) == Some(MatchResult::Draw));
+    }
+
+    #[test]
+    fn time_over_goes_to_the_player_with_more_health() {
+        let mut state = new_close_fight();
+        close_fight(&mut state, 400, 700);
+        state.timer = Some(1);
+        assert!(finish_round(&mut state, "5", "5") == MatchResult::Player2Win);
+        assert_eq!(state.player_2.win_count, 1);
+
+        let mut state = new_close_fight();
+        close_fight(&mut state, 500, 500);
+        state.timer = Some(1);
+        assert!(finish_round(&mut state, "5", "5") == MatchResult::Draw);
+    }
+
+    #[test]
+    fn the_clock_counts_down_during_the_fight() {
+        let mut state = new_fight();
+        let round_frames = state.settings.round_frames().unwrap();
+
+        hold(&mut state, 10, "5", "5");
+        assert_eq!(state.timer, Some(round_frames - 10));
+    }
+
+    #[test]
+    fn the_clock_stops_during_hitstop_and_super_flash() {
+        let mut state = new_fight();
+        let round_frames = state.settings.round_frames().unwrap();
+
+        state.player_2.freeze_frames = 10;
+        hold(&mut state, 9, "5", "5");
+        assert_eq!(state.timer, Some(round_frames));
+
+        let mut state = new_fight();
+        state.player_1.character_stats.super_meter = state.player_1.max_super_meter();
+        for notation in ["2", "3", "6cs"] {
+            state.update(input(notation), input("5"));
+        }
+        hold(&mut state, 20, "5", "5");
+        assert_eq!(state.timer, Some(round_frames - 2));
+    }
+
+    #[test]
+    fn the_clock_only_runs_during_the_fight() {
+        let mut state = new_fight();
+        state.start_round();
+        let round_frames = state.settings.round_frames().unwrap();
+        hold(&mut state, 10, "5", "5");
+        assert!(matches!(state.phase, RoundPhase::Intro(_)));
+        assert_eq!(state.timer, Some(round_frames));
+
+        state.phase = RoundPhase::RoundOver {
+            result: MatchResult::Draw,
+            frames: 100,
+        };
+        hold(&mut state, 10, "5", "5");
+        assert_eq!(state.timer, Some(round_frames));
+    }
+
+    #[test]
+    fn rounds_without_a_time_limit_never_time_out() {
+        let mut state = new_fight();
+        state.settings = MatchSettings {
+            round_seconds: None,
+            ..MatchSettings::default()
+        };
+        state.start_round();
+        state.phase = RoundPhase::Fight;
+
+        let frames = 100 * state.settings.frames_per_second;
+        hold(&mut state, frames, "5", "5");
+        assert!(state.timer.is_none());
+        assert!(matches!(state.phase, RoundPhase::Fight));
+    }
+}
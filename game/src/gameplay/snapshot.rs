@@ -0,0 +1,189 @@
+use std::hash::{Hash, Hasher};
+
+use super::{MatchState, Move, PlayerGameState, Projectile};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A saved copy of everything in a match which changes from frame to frame,
+/// for rewinding and resimulating it. The character definitions and move
+/// data are static, so only references to them are kept.
+#[derive(Clone)]
+pub struct MatchSnapshot {
+    state: MatchState,
+}
+
+impl MatchSnapshot {
+    pub fn checksum(&self) -> u64 {
+        self.state.checksum()
+    }
+}
+
+impl MatchState {
+    pub fn save_state(&self) -> MatchSnapshot {
+        MatchSnapshot {
+            state: self.clone(),
+        }
+    }
+
+    /// Rewinds the match to the snapshot, reusing the existing allocations.
+    pub fn load_state(&mut self, snapshot: &MatchSnapshot) {
+        self.clone_from(&snapshot.state);
+    }
+
+    /// A hash of the whole simulation state, which matches between two
+    /// matches exactly when they'll play out the same given the same inputs.
+    pub fn checksum(&self) -> u64 {
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// The standard library's hasher is randomly seeded and may change between
+/// releases, so checksums use FNV-1a, which is the same everywhere.
+///
+/// `usize` and `isize` are hashed at 64 bits, so that the wasm32 runtime and
+/// native builds agree. Lengths and enum discriminants are hashed as them too.
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write(&(value as u64).to_le_bytes());
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write(&(value as i64).to_le_bytes());
+    }
+}
+
+impl Hash for MatchState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.settings.hash(state);
+        self.phase.hash(state);
+        self.round.hash(state);
+        self.timer.hash(state);
+        self.player_1.hash(state);
+        self.player_2.hash(state);
+        self.projectiles.hash(state);
+    }
+}
+
+impl Hash for PlayerGameState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.character.name.hash(state);
+        self.character_stats.hash(state);
+        self.win_count.hash(state);
+        self.input_buffer.hash(state);
+        self.pos_x.hash(state);
+        self.pos_y.hash(state);
+        self.vel_x.hash(state);
+        self.vel_y.hash(state);
+        self.pushback_x.hash(state);
+        self.facing.hash(state);
+        self.juggle_points.hash(state);
+        self.character_state.hash(state);
+        self.freeze_frames.hash(state);
+        self.super_flash.hash(state);
+    }
+}
+
+/// Projectiles of the same move share their data, so the owner's move
+/// isn't needed to tell them apart.
+impl Hash for Projectile {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.owner.hash(state);
+        self.pos_x.hash(state);
+        self.pos_y.hash(state);
+        self.vel_x.hash(state);
+        self.facing.hash(state);
+        self.lifetime.hash(state);
+        self.hits_left.hash(state);
+        self.cooldown.hash(state);
+    }
+}
+
+/// Each move in a command list has its own command, so that's enough to
+/// identify it without hashing all of its data.
+impl Hash for Move {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.command.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{Hash, Hasher};
+
+    use crate::db::DEFAULT_CHARACTER;
+
+    use super::super::{
+        MatchSettings, MatchState,
+        testing::{SCRIPTED_FRAMES, scripted_inputs},
+    };
+    use super::{FNV_OFFSET_BASIS, Fnv1a};
+
+    fn advance(state: &mut MatchState, frames: std::ops::Range<usize>) {
+        for frame in frames {
+            let (player_1, player_2) = scripted_inputs(frame);
+            state.update(player_1, player_2);
+        }
+    }
+
+    #[test]
+    fn loading_a_snapshot_resimulates_identically() {
+        let mut state = MatchState::new(
+            &DEFAULT_CHARACTER,
+            &DEFAULT_CHARACTER,
+            MatchSettings::default(),
+        );
+        advance(&mut state, 0..SCRIPTED_FRAMES / 2);
+
+        let snapshot = state.save_state();
+        assert_eq!(snapshot.checksum(), state.checksum());
+
+        advance(&mut state, SCRIPTED_FRAMES / 2..SCRIPTED_FRAMES);
+        let first = state.checksum();
+        assert_ne!(first, snapshot.checksum());
+
+        state.load_state(&snapshot);
+        assert_eq!(state.checksum(), snapshot.checksum());
+
+        advance(&mut state, SCRIPTED_FRAMES / 2..SCRIPTED_FRAMES);
+        assert_eq!(state.checksum(), first);
+    }
+
+    fn hash_of(value: impl Hash) -> u64 {
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn checksums_are_the_same_on_every_platform() {
+        // The published FNV-1a test vector.
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        // Integers are hashed as 8 little-endian bytes, whatever their size
+        // on the platform.
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        hasher.write(&[0xd2, 0x04, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(hasher.finish(), 0xfd35_4f73_d915_bd6b);
+        assert_eq!(hash_of(1234usize), hasher.finish());
+        assert_eq!(hash_of(1234usize), hash_of(1234u64));
+        assert_eq!(hash_of(-1234isize), hash_of(-1234i64));
+    }
+}
@@ -0,0 +1,374 @@
+use super::{
+    CharacterState, HorizontalInput, InputCommand, Move, PlayerGameState, VerticalInput,
+    command_list::COMMAND_BUFFER_WINDOW,
+};
+
+const DASH_FORWARD: InputCommand = InputCommand::from_notation("66");
+const DASH_BACKWARD: InputCommand = InputCommand::from_notation("44");
+
+impl PlayerGameState {
+    /// Advances the character by a single frame, counting through the
+    /// current state and then moving on to whatever `next_state` decides.
+    pub(super) fn update(&mut self) {
+        self.regenerate_boost();
+
+        // Cancels are checked before advancing, so a move which connected on
+        // its last cancellable frame can still be canceled after hitstop.
+        let cancel = self.find_cancel();
+
+        self.advance_state();
+        if let Some(next) = self.next_state(cancel) {
+            self.change_state(next);
+        }
+    }
+
+    /// Leaves the current state for `next`, running the exit hook of the
+    /// old state and the entry hook of the new one. Every state change
+    /// goes through here, including those caused by hits and throws.
+    pub(super) fn change_state(&mut self, next: CharacterState) {
+        self.exit_state();
+        self.character_state = next;
+        self.enter_state();
+    }
+
+    /// Steps the frame counters of timed states.
+    fn advance_state(&mut self) {
+        match &mut self.character_state {
+            CharacterState::Attacking { frame, .. }
+            | CharacterState::Throwing { frame, .. }
+            | CharacterState::Thrown { frame, .. }
+            | CharacterState::Dashing { frame, .. } => {
+                *frame += 1;
+            }
+            CharacterState::Landing(frames)
+            | CharacterState::Boosting(frames)
+            | CharacterState::Hitstun(frames)
+            | CharacterState::Blockstun(frames)
+            | CharacterState::KnockedDown(frames)
+            | CharacterState::WakingUp(frames) => {
+                *frames = frames.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Decides what the character does next, or None to stay in the current
+    /// state. Cancels come first, then boosts, then timed states which have
+    /// finished, and then whatever the (possibly new) state allows: starting
+    /// a move from the input buffer, or moving around according to the
+    /// direction held.
+    fn next_state(&self, cancel: Option<&'static Move>) -> Option<CharacterState> {
+        if let Some(attack) = cancel {
+            return Some(attacking(attack));
+        }
+
+        if self.boost_input() {
+            return Some(CharacterState::Boosting(self.character.boost_frames));
+        }
+
+        let finished = self.finished_state();
+        let state = finished.as_ref().unwrap_or(&self.character_state);
+
+        if let Some(attack) = self.character.command_list.get_move(
+            &self.input_buffer,
+            state,
+            self.character_stats.super_meter,
+        ) {
+            return Some(attacking(attack));
+        }
+
+        if state.allowed_actions().movement
+            && let Some(movement) = self.movement_state(state)
+        {
+            return Some(movement);
+        }
+
+        finished
+    }
+
+    /// The state to move on to if the current one has run its course.
+    fn finished_state(&self) -> Option<CharacterState> {
+        match &self.character_state {
+            CharacterState::Attacking { attack, frame, .. } => {
+                (*frame >= attack.data.get_total_frames()).then(|| self.neutral_state())
+            }
+            CharacterState::Dashing { backward, frame } => {
+                let duration = if *backward {
+                    self.character.backward_dash_frames
+                } else {
+                    self.character.forward_dash_frames
+                };
+                (*frame >= duration).then(|| self.neutral_state())
+            }
+            CharacterState::KnockedDown(0) if self.character_stats.health == 0 => None,
+            CharacterState::KnockedDown(0) => {
+                Some(CharacterState::WakingUp(self.character.wakeup_frames))
+            }
+            CharacterState::Landing(0)
+            | CharacterState::Boosting(0)
+            | CharacterState::Hitstun(0)
+            | CharacterState::Blockstun(0)
+            | CharacterState::WakingUp(0) => Some(self.neutral_state()),
+            _ => None,
+        }
+    }
+
+    /// The state to return to once nothing else is happening. Characters
+    /// with no health left stay down instead.
+    fn neutral_state(&self) -> CharacterState {
+        if self.is_airborne() {
+            CharacterState::Jumping
+        } else if self.character_stats.health == 0 {
+            CharacterState::KnockedDown(0)
+        } else {
+            CharacterState::Idle
+        }
+    }
+
+    /// Jumps, dashes, crouches or walks according to the inputs, returning
+    /// None if that's what the character is already doing in `state`.
+    fn movement_state(&self, state: &CharacterState) -> Option<CharacterState> {
+        let direction = self.input_buffer.get_last_entry().state.direction;
+
+        let next = if direction.vertical == VerticalInput::Up {
+            CharacterState::Jumping
+        } else if self
+            .input_buffer
+            .matches(&DASH_FORWARD, COMMAND_BUFFER_WINDOW)
+        {
+            CharacterState::Dashing {
+                backward: false,
+                frame: 0,
+            }
+        } else if self
+            .input_buffer
+            .matches(&DASH_BACKWARD, COMMAND_BUFFER_WINDOW)
+        {
+            CharacterState::Dashing {
+                backward: true,
+                frame: 0,
+            }
+        } else if direction.vertical == VerticalInput::Down {
+            CharacterState::Crouching
+        } else {
+            match direction.horizontal {
+                HorizontalInput::Forward => CharacterState::Walking { backward: false },
+                HorizontalInput::Backward => CharacterState::Walking { backward: true },
+                HorizontalInput::Neutral => CharacterState::Idle,
+            }
+        };
+
+        let unchanged = match (state, &next) {
+            (CharacterState::Idle, CharacterState::Idle)
+            | (CharacterState::Crouching, CharacterState::Crouching) => true,
+            (
+                CharacterState::Walking { backward },
+                CharacterState::Walking {
+                    backward: next_backward,
+                },
+            ) => backward == next_backward,
+            _ => false,
+        };
+
+        (!unchanged).then_some(next)
+    }
+
+    fn exit_state(&mut self) {
+        if matches!(
+            self.character_state,
+            CharacterState::Walking { .. }
+                | CharacterState::Dashing { .. }
+                | CharacterState::Boosting(_)
+        ) {
+            self.vel_x = 0;
+        }
+    }
+
+    fn enter_state(&mut self) {
+        let character = self.character;
+        let sign = self.facing.sign();
+
+        match self.character_state {
+            // Jumping is also the neutral state in the air, which shouldn't
+            // launch the character a second time.
+            CharacterState::Jumping if !self.is_airborne() => {
+                let direction = self.input_buffer.get_last_entry().state.direction;
+                let speed = match direction.horizontal {
+                    HorizontalInput::Forward => character.jump_forward_speed,
+                    HorizontalInput::Backward => -character.jump_backward_speed,
+                    HorizontalInput::Neutral => 0,
+                };
+                self.vel_x = sign * speed;
+                self.vel_y = -character.jump_velocity;
+            }
+            CharacterState::Walking { backward } => {
+                self.vel_x = if backward {
+                    -sign * character.backward_walk_speed
+                } else {
+                    sign * character.forward_walk_speed
+                };
+            }
+            CharacterState::Dashing { backward, .. } => {
+                self.vel_x = if backward {
+                    -sign * character.backward_dash_speed
+                } else {
+                    sign * character.forward_dash_speed
+                };
+            }
+            CharacterState::Attacking { attack, .. } => self.start_attack(attack),
+            // The BS chord would otherwise also be read as B and S presses by
+            // the moves boosting allows, or start another boost afterwards.
+            CharacterState::Boosting(_) => {
+                self.input_buffer.consume();
+                self.character_stats.boost_meter -= character.boost_cost;
+                self.vel_x = sign * character.boost_speed;
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the character is low to the ground, either crouching or
+    /// holding down while blocking.
+    pub fn is_crouching(&self) -> bool {
+        match self.character_state {
+            CharacterState::Crouching => true,
+            CharacterState::Guarding | CharacterState::Blockstun(_) => {
+                self.input_buffer.get_last_entry().state.direction.vertical == VerticalInput::Down
+            }
+            _ => false,
+        }
+    }
+}
+
+fn attacking(attack: &'static Move) -> CharacterState {
+    CharacterState::Attacking {
+        attack,
+        frame: 0,
+        contact: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, MatchState,
+        testing::{close_attack, hold, input, new_fight},
+    };
+
+    /// Holds the direction for a couple of frames, returning player 1's state.
+    fn holding<'a>(state: &'a mut MatchState, direction: &str) -> &'a CharacterState {
+        hold(state, 2, direction, "5");
+        &state.player_1.character_state
+    }
+
+    #[test]
+    fn directions_crouch_and_walk() {
+        let mut state = new_fight();
+        assert!(matches!(
+            holding(&mut state, "2"),
+            CharacterState::Crouching
+        ));
+        assert!(matches!(
+            holding(&mut state, "3"),
+            CharacterState::Crouching
+        ));
+        assert!(matches!(
+            holding(&mut state, "6"),
+            CharacterState::Walking { backward: false }
+        ));
+        assert!(matches!(
+            holding(&mut state, "4"),
+            CharacterState::Walking { backward: true }
+        ));
+        assert!(matches!(
+            holding(&mut state, "1"),
+            CharacterState::Crouching
+        ));
+        assert!(matches!(holding(&mut state, "5"), CharacterState::Idle));
+    }
+
+    #[test]
+    fn dashes_cover_a_set_distance() {
+        let mut state = new_fight();
+        let character = state.player_1.character;
+        hold(&mut state, 1, "6", "5");
+        hold(&mut state, 1, "5", "5");
+        let start = state.player_1.pos_x;
+
+        hold(&mut state, 1, "6", "5");
+        assert!(matches!(
+            state.player_1.character_state,
+            CharacterState::Dashing {
+                backward: false,
+                ..
+            }
+        ));
+
+        hold(&mut state, character.forward_dash_frames, "5", "5");
+        assert!(matches!(
+            state.player_1.character_state,
+            CharacterState::Idle
+        ));
+        assert_eq!(
+            state.player_1.pos_x,
+            start + character.forward_dash_speed * character.forward_dash_frames as isize
+        );
+    }
+
+    #[test]
+    fn backdashes_cant_be_attacked_out_of() {
+        let mut state = new_fight();
+        for direction in ["4", "5", "4"] {
+            state.update(input(direction), input("5"));
+        }
+
+        state.update(input("5a"), input("5"));
+        assert!(matches!(
+            state.player_1.character_state,
+            CharacterState::Dashing { backward: true, .. }
+        ));
+    }
+
+    #[test]
+    fn jumps_end_with_landing_recovery() {
+        let mut state = new_fight();
+        let landing_frames = state.player_1.character.landing_frames;
+
+        state.update(input("8"), input("5"));
+        while state.player_1.is_airborne() {
+            hold(&mut state, 1, "5", "5");
+        }
+        assert!(matches!(
+            state.player_1.character_state,
+            CharacterState::Landing(_)
+        ));
+
+        hold(&mut state, landing_frames - 1, "5", "5");
+        assert!(matches!(
+            state.player_1.character_state,
+            CharacterState::Landing(_)
+        ));
+        hold(&mut state, 1, "5", "5");
+        assert!(matches!(
+            state.player_1.character_state,
+            CharacterState::Idle
+        ));
+    }
+
+    #[test]
+    fn waking_up_is_invincible() {
+        let mut state = close_attack("5a");
+        let wakeup_frames = state.player_2.character.wakeup_frames;
+        state.player_2.character_state = CharacterState::WakingUp(wakeup_frames);
+
+        hold(&mut state, wakeup_frames, "5", "5");
+        assert_eq!(
+            state.player_2.character_stats.health,
+            state.player_2.character.max_hp
+        );
+        assert!(matches!(
+            state.player_2.character_state,
+            CharacterState::Idle
+        ));
+    }
+}
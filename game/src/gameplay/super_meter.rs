@@ -0,0 +1,74 @@
+use super::{MatchState, Move, PlayerGameState};
+
+impl MatchState {
+    /// Freezes both players when either starts a super, so that the flash
+    /// announces it and the super's startup plays out afterwards, when the
+    /// opponent can react to and block it.
+    pub(super) fn apply_super_flash(&mut self) {
+        let p1_flash = std::mem::take(&mut self.player_1.super_flash);
+        let p2_flash = std::mem::take(&mut self.player_2.super_flash);
+        let flash = p1_flash.max(p2_flash);
+
+        self.player_1.freeze_frames = self.player_1.freeze_frames.max(flash);
+        self.player_2.freeze_frames = self.player_2.freeze_frames.max(flash);
+    }
+}
+
+impl PlayerGameState {
+    pub fn max_super_meter(&self) -> usize {
+        self.character.super_stock_size * self.character.super_stocks
+    }
+
+    pub(super) fn gain_meter(&mut self, amount: usize) {
+        let max = self.max_super_meter();
+        let meter = &mut self.character_stats.super_meter;
+        *meter = (*meter + amount).min(max);
+    }
+
+    /// Pays for a move as it starts, and gains its whiff meter.
+    pub(super) fn start_attack(&mut self, attack: &'static Move) {
+        self.character_stats.super_meter -= attack.data.meter_cost;
+        self.gain_meter(attack.data.whiff_meter_gain);
+        self.super_flash = attack.data.super_flash_frames;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState,
+        testing::{input, new_fight},
+    };
+
+    #[test]
+    fn supers_can_be_blocked_once_the_flash_ends() {
+        let mut state = new_fight();
+        state.player_1.pos_x = -300;
+        state.player_2.pos_x = 300;
+        state.player_1.character_stats.super_meter = state.player_1.max_super_meter();
+
+        for notation in ["2", "3", "6cs"] {
+            state.update(input(notation), input("5"));
+        }
+        let flash = state.player_2.freeze_frames;
+        assert!(flash > 0);
+        assert_eq!(state.player_1.freeze_frames, flash);
+
+        // Player 2 only starts holding back once the flash is over.
+        for _ in 0..flash {
+            state.update(input("5"), input("5"));
+        }
+        assert_eq!(state.player_2.freeze_frames, 0);
+
+        let mut blocked = false;
+        for _ in 0..20 {
+            state.update(input("5"), input("4"));
+            blocked |= matches!(state.player_2.character_state, CharacterState::Blockstun(_));
+            assert!(!matches!(
+                state.player_2.character_state,
+                CharacterState::Hitstun(_) | CharacterState::Juggle { .. }
+            ));
+        }
+        assert!(blocked);
+    }
+}
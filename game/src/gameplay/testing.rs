@@ -0,0 +1,91 @@
+use crate::db::DEFAULT_CHARACTER;
+
+use super::{
+    CharacterState, DirectionInput, InputCommand, InputState, MatchSettings, MatchState, Move,
+    RoundPhase,
+};
+
+/// How many frames tests which play out a match with `scripted_inputs` run for.
+pub(crate) const SCRIPTED_FRAMES: usize = 600;
+
+/// Both players' inputs for the frame. Each walks forwards pressing buttons
+/// in a repeating pattern, out of step with the other, so the players close
+/// in, attack and get hit, and predicting either from the last frame is
+/// often wrong.
+pub(crate) fn scripted_inputs(frame: usize) -> (InputState, InputState) {
+    (scripted_input(frame), scripted_input(frame * 3 + 7))
+}
+
+fn scripted_input(frame: usize) -> InputState {
+    let notation = ['6', '6', '3', '2', '5', '6'][frame / 7 % 6];
+    InputState {
+        direction: DirectionInput::from_notation(&notation),
+        a_pressed: frame % 11 < 2,
+        b_pressed: frame % 17 < 2,
+        c_pressed: frame % 23 < 2,
+        s_pressed: frame % 29 < 2,
+    }
+}
+
+/// A match between two default characters which skips the round intro.
+pub(crate) fn new_fight() -> MatchState {
+    let mut state = MatchState::new(
+        &DEFAULT_CHARACTER,
+        &DEFAULT_CHARACTER,
+        MatchSettings::default(),
+    );
+    state.phase = RoundPhase::Fight;
+    state
+}
+
+/// Like `new_fight`, but with the players close enough for jabs and throws
+/// to reach each other.
+pub(crate) fn new_close_fight() -> MatchState {
+    let mut state = new_fight();
+    state.player_1.pos_x = -300;
+    state.player_2.pos_x = 300;
+    state
+}
+
+/// A close fight where player 1 has just started the move `notation`.
+pub(crate) fn close_attack(notation: &str) -> MatchState {
+    let mut state = new_close_fight();
+    hold(&mut state, 1, notation, "5");
+    state
+}
+
+/// A close fight with player 1 falling onto player 2 with an air attack,
+/// which hits high.
+pub(crate) fn jump_in() -> MatchState {
+    let mut state = new_close_fight();
+    state.player_1.pos_y = -800;
+    state.player_1.character_state = CharacterState::Attacking {
+        attack: find_move("j.5c"),
+        frame: 0,
+        contact: None,
+    };
+    state
+}
+
+/// Plays `frames` frames with each player holding the same inputs.
+pub(crate) fn hold(state: &mut MatchState, frames: usize, player_1: &str, player_2: &str) {
+    for _ in 0..frames {
+        state.update(input(player_1), input(player_2));
+    }
+}
+
+/// The default character's move with the command, such as `j.5c`.
+pub(crate) fn find_move(notation: &'static str) -> &'static Move {
+    let command = InputCommand::from_notation(notation);
+    DEFAULT_CHARACTER
+        .command_list
+        .moves
+        .iter()
+        .find(|m| m.command == command)
+        .unwrap()
+}
+
+/// Inputs in the notation `InputState::from_notation` reads, such as `2b`.
+pub(crate) fn input(notation: &str) -> InputState {
+    InputState::from_notation(notation).unwrap()
+}
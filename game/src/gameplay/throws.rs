@@ -0,0 +1,214 @@
+use super::{
+    AttackData, ButtonInput, CharacterState, Facing, MatchState, PlayerGameState, ThrowData,
+};
+
+/// Blockstun both players recover from after a throw is teched.
+const THROW_TECH_STUN: usize = 12;
+/// How far apart both players are pushed after a throw is teched.
+const THROW_TECH_DISTANCE: isize = 1200;
+/// Frames before a throw connects in which pressing AS still techs it.
+const THROW_TECH_BUFFER: usize = 2;
+
+impl MatchState {
+    /// Progresses throws which have already connected, then starts any new
+    /// ones. Throws ignore blocking, and two throws connecting on the same
+    /// frame tech each other.
+    pub(super) fn resolve_throws(&mut self) {
+        let (p1_facing, p2_facing) = self.facings();
+        progress_throw(&mut self.player_1, p1_facing, &mut self.player_2);
+        progress_throw(&mut self.player_2, p2_facing, &mut self.player_1);
+
+        let p1_throw = self
+            .player_1
+            .find_throw(p1_facing, &self.player_2, p2_facing);
+        let p2_throw = self
+            .player_2
+            .find_throw(p2_facing, &self.player_1, p1_facing);
+
+        match (p1_throw, p2_throw) {
+            (Some(_), Some(_)) => tech_throw(&mut self.player_1, p1_facing, &mut self.player_2),
+            (Some(throw), None) => {
+                start_throw(&mut self.player_1, p1_facing, &mut self.player_2, throw)
+            }
+            (None, Some(throw)) => {
+                start_throw(&mut self.player_2, p2_facing, &mut self.player_1, throw)
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn start_throw(
+    attacker: &mut PlayerGameState,
+    facing: Facing,
+    defender: &mut PlayerGameState,
+    throw: &'static ThrowData,
+) {
+    attacker.change_state(CharacterState::Throwing { throw, frame: 0 });
+    defender.change_state(CharacterState::Thrown { throw, frame: 0 });
+    defender.pos_x = attacker.pos_x + facing.sign() * throw.hold_offset;
+    defender.pos_y = attacker.pos_y;
+    attacker.vel_x = 0;
+    defender.vel_x = 0;
+    attacker.pushback_x = 0;
+    defender.pushback_x = 0;
+}
+
+/// Techs the throw if the defender pressed AS within the tech window, which
+/// counts from just before the throw connected, otherwise releases the
+/// defender once the throw has finished.
+fn progress_throw(attacker: &mut PlayerGameState, facing: Facing, defender: &mut PlayerGameState) {
+    let CharacterState::Throwing { throw, frame } = attacker.character_state else {
+        return;
+    };
+
+    if !matches!(defender.character_state, CharacterState::Thrown { .. }) {
+        attacker.change_state(CharacterState::Idle);
+    } else if frame < throw.tech_window
+        && defender
+            .input_buffer
+            .pressed(ButtonInput::AS, frame + THROW_TECH_BUFFER)
+    {
+        tech_throw(attacker, facing, defender);
+    } else if frame >= throw.duration {
+        let health = &mut defender.character_stats.health;
+        *health = health.saturating_sub(throw.damage);
+        defender.pos_x = attacker.pos_x + facing.sign() * throw.release_offset;
+        defender.change_state(CharacterState::KnockedDown(throw.knockdown_frames));
+        attacker.change_state(CharacterState::Idle);
+    }
+}
+
+fn tech_throw(attacker: &mut PlayerGameState, facing: Facing, defender: &mut PlayerGameState) {
+    defender.pos_x = attacker.pos_x + facing.sign() * THROW_TECH_DISTANCE;
+    attacker.change_state(CharacterState::Blockstun(THROW_TECH_STUN));
+    defender.change_state(CharacterState::Blockstun(THROW_TECH_STUN));
+}
+
+impl PlayerGameState {
+    /// Returns the throw grabbing the opponent this frame, if the current
+    /// segment has a throw hitbox overlapping a throwable opponent.
+    fn find_throw(
+        &self,
+        facing: Facing,
+        opponent: &PlayerGameState,
+        opponent_facing: Facing,
+    ) -> Option<&'static ThrowData> {
+        if !opponent.character_state.can_be_thrown() {
+            return None;
+        }
+
+        let CharacterState::Attacking { attack, frame, .. } = &self.character_state else {
+            return None;
+        };
+
+        let (_, frame_data) = attack.data.get_frame_data(*frame)?;
+        let hitboxes = frame_data.hitboxes.as_ref()?;
+        let AttackData::Throw(throw) = &hitboxes.attack_data else {
+            return None;
+        };
+
+        self.hitboxes_overlap(hitboxes, facing, opponent, opponent_facing)
+            .then_some(throw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        CharacterState, MatchState,
+        testing::{hold, input, new_close_fight},
+    };
+    use super::THROW_TECH_BUFFER;
+
+    /// The tech window of the default character's throws.
+    const TECH_WINDOW: usize = 10;
+
+    /// Player 1 throws player 2, who presses AS on `tech_frame` if given,
+    /// counting from the frame the throw connects. Returns whether the throw
+    /// was teched.
+    fn throw(tech_frame: Option<isize>) -> bool {
+        let mut state = new_close_fight();
+        // The throw's startup frames.
+        let connect_frame = 3;
+
+        for frame in 0..connect_frame + 60 {
+            let player_1 = if frame == 0 { "5as" } else { "5" };
+            let relative_frame = frame as isize - connect_frame as isize;
+            let player_2 = if tech_frame == Some(relative_frame) {
+                "5as"
+            } else {
+                "5"
+            };
+            state.update(input(player_1), input(player_2));
+
+            if frame == connect_frame {
+                assert!(matches!(
+                    state.player_2.character_state,
+                    CharacterState::Thrown { frame: 0, .. }
+                ));
+            }
+        }
+
+        state.player_2.character_stats.health == state.player_2.character.max_hp
+    }
+
+    /// Whether player 1's throw connects with player 2 as set up in `state`.
+    fn connects(mut state: MatchState) -> bool {
+        state.update(input("5as"), input("5"));
+        (0..30).any(|_| {
+            hold(&mut state, 1, "5", "5");
+            matches!(
+                state.player_2.character_state,
+                CharacterState::Thrown { .. }
+            )
+        })
+    }
+
+    #[test]
+    fn throws_land_without_a_tech() {
+        assert!(!throw(None));
+    }
+
+    #[test]
+    fn techs_pressed_just_before_the_throw_connects() {
+        assert!(throw(Some(-(THROW_TECH_BUFFER as isize))));
+        assert!(throw(Some(-1)));
+    }
+
+    #[test]
+    fn techs_pressed_late_in_the_window() {
+        assert!(throw(Some(0)));
+        assert!(throw(Some(TECH_WINDOW as isize - 1)));
+    }
+
+    #[test]
+    fn techs_after_the_window_expires_do_nothing() {
+        assert!(!throw(Some(TECH_WINDOW as isize)));
+        assert!(!throw(Some(TECH_WINDOW as isize + 5)));
+    }
+
+    #[test]
+    fn throws_connect_with_grounded_opponents() {
+        assert!(connects(new_close_fight()));
+    }
+
+    #[test]
+    fn throws_whiff_on_airborne_opponents() {
+        let mut state = new_close_fight();
+        state.player_2.character_state = CharacterState::Jumping;
+        state.player_2.pos_y = -400;
+        assert!(!connects(state));
+    }
+
+    #[test]
+    fn throws_whiff_on_stunned_opponents() {
+        let mut state = new_close_fight();
+        state.player_2.character_state = CharacterState::Hitstun(30);
+        assert!(!connects(state));
+
+        let mut state = new_close_fight();
+        state.player_2.character_state = CharacterState::Blockstun(30);
+        assert!(!connects(state));
+    }
+}
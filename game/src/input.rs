@@ -0,0 +1,83 @@
+use crate::api::*;
+use crate::gameplay::{DirectionInput, Facing, InputState, VerticalInput};
+
+const ANALOG_DEADZONE: f32 = 0.5;
+
+/// Raw button state for a single player as reported by the host.
+struct HostInput {
+    up: bool,
+    down: bool,
+    left: bool,
+    right: bool,
+    a: bool,
+    b: bool,
+    c: bool,
+    d: bool,
+}
+
+impl HostInput {
+    fn poll(player_id: i32) -> Self {
+        unsafe {
+            let stick_x = analog_left_x(player_id);
+            let stick_y = analog_left_y(player_id);
+
+            Self {
+                up: button_up_held(player_id) != 0 || stick_y > ANALOG_DEADZONE,
+                down: button_down_held(player_id) != 0 || stick_y < -ANALOG_DEADZONE,
+                left: button_left_held(player_id) != 0 || stick_x < -ANALOG_DEADZONE,
+                right: button_right_held(player_id) != 0 || stick_x > ANALOG_DEADZONE,
+                a: button_a_held(player_id) != 0,
+                b: button_b_held(player_id) != 0,
+                c: button_c_held(player_id) != 0,
+                d: button_d_held(player_id) != 0,
+            }
+        }
+    }
+
+    fn to_input_state(&self, facing: Facing) -> InputState {
+        let vertical = match (self.up, self.down) {
+            (true, false) => VerticalInput::Up,
+            (false, true) => VerticalInput::Down,
+            _ => VerticalInput::Neutral,
+        };
+
+        InputState {
+            direction: DirectionInput {
+                vertical,
+                horizontal: facing.horizontal_input(self.left, self.right),
+            },
+            a_pressed: self.a,
+            b_pressed: self.b,
+            c_pressed: self.c,
+            s_pressed: self.d,
+        }
+    }
+}
+
+/// Samples the host inputs for the given player this frame, with
+/// directions made relative to the way the player is facing.
+pub fn poll_player(player_id: i32, facing: Facing) -> InputState {
+    HostInput::poll(player_id).to_input_state(facing)
+}
+
+/// Buttons for controlling replay playback: start pauses, select steps a
+/// frame while paused, and the right shoulder fast-forwards while held.
+pub struct PlaybackControls {
+    pub toggle_pause: bool,
+    pub step: bool,
+    pub fast_forward: bool,
+}
+
+pub fn poll_playback_controls(player_id: i32) -> PlaybackControls {
+    unsafe {
+        PlaybackControls {
+            toggle_pause: button_start_pressed(player_id) != 0,
+            step: button_select_pressed(player_id) != 0,
+            fast_forward: button_right_shoulder_held(player_id) != 0,
+        }
+    }
+}
+
+pub fn start_pressed(player_id: i32) -> bool {
+    unsafe { button_start_pressed(player_id) != 0 }
+}
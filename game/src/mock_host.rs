@@ -0,0 +1,516 @@
+//! A native stand-in for the runtime, implementing the imports in `api.rs`
+//! so that `init`, `update` and `render` can be run and tested outside it.
+//!
+//! The host records everything the game asks it to do as `HostCommand`s,
+//! and reports buttons as held on the frames they've been scripted for. Its
+//! state is per thread, so each test gets a host of its own.
+
+// The imports are only called through `api.rs`, with pointers to as many
+// bytes as the runtime would read.
+#![allow(clippy::missing_safety_doc)]
+
+use std::{cell::RefCell, collections::HashMap, ops::Range};
+
+use glam::{Mat4, Vec3};
+
+pub const WIDTH: i32 = 1280;
+pub const HEIGHT: i32 = 720;
+pub const FPS: i32 = 60;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Button {
+    Up,
+    Down,
+    Left,
+    Right,
+    A,
+    B,
+    C,
+    D,
+    Start,
+    Select,
+    LeftShoulder,
+    RightShoulder,
+    LeftStick,
+    RightStick,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl Button {
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// A call the game made to the host, with what it was given.
+#[derive(Clone, PartialEq, Debug)]
+pub enum HostCommand {
+    Log(String),
+    SetSeed(i32),
+    PushAudio {
+        len: i32,
+        channel_count: i32,
+        sample_rate: i32,
+    },
+    LoadTexture {
+        id: i32,
+        width: i32,
+        height: i32,
+        has_alpha: i32,
+    },
+    LoadStaticMesh {
+        id: i32,
+        len: i32,
+        pipeline: i32,
+    },
+    LoadStaticMeshIndexed {
+        id: i32,
+        len: i32,
+        index_len: i32,
+        pipeline: i32,
+    },
+    ProjMatrix(Mat4),
+    ViewMatrix {
+        view: Mat4,
+        pos: Vec3,
+    },
+    ModelMatrix(Mat4),
+    SetTexture {
+        id: i32,
+        layer: i32,
+        blend: i32,
+    },
+    SetMatcap {
+        id: i32,
+        layer: i32,
+        blend: i32,
+    },
+    SetWindingOrder(i32),
+    DrawTriList {
+        len: i32,
+        pipeline: i32,
+    },
+    DrawTriListIndexed {
+        len: i32,
+        index_len: i32,
+        pipeline: i32,
+    },
+    DrawStaticMesh(i32),
+    DrawStaticMeshIndexed(i32),
+    DrawSprite(i32),
+}
+
+#[derive(Default)]
+struct MockHost {
+    frame: usize,
+    /// The buttons held by each player on each scripted frame, as bits.
+    held: HashMap<(i32, usize), u16>,
+    commands: Vec<HostCommand>,
+    textures: i32,
+    meshes: i32,
+    random: u64,
+    /// Overrides `FPS`, for testing hosts which report nonsense.
+    fps: Option<i32>,
+}
+
+impl MockHost {
+    fn held_on(&self, player_id: i32, frame: usize) -> u16 {
+        self.held.get(&(player_id, frame)).copied().unwrap_or(0)
+    }
+
+    fn held(&self, player_id: i32) -> u16 {
+        self.held_on(player_id, self.frame)
+    }
+
+    fn previously_held(&self, player_id: i32) -> u16 {
+        match self.frame.checked_sub(1) {
+            Some(frame) => self.held_on(player_id, frame),
+            None => 0,
+        }
+    }
+
+    /// Steps a splitmix64 generator, so random numbers only depend on the seed.
+    fn next_random(&mut self) -> u64 {
+        self.random = self.random.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.random;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+thread_local! {
+    static HOST: RefCell<MockHost> = RefCell::default();
+}
+
+/// Clears the script, the recorded commands and loaded resources, and goes
+/// back to frame 0.
+pub fn reset() {
+    HOST.set(MockHost::default());
+}
+
+/// Holds the buttons for the player on each of the frames.
+pub fn hold(player_id: i32, frames: Range<usize>, buttons: &[Button]) {
+    let bits = buttons.iter().fold(0, |bits, button| bits | button.bit());
+    HOST.with_borrow_mut(|host| {
+        for frame in frames {
+            *host.held.entry((player_id, frame)).or_default() |= bits;
+        }
+    });
+}
+
+/// Reports the frame rate as `fps` rather than `FPS` until the next reset.
+pub fn set_fps(fps: i32) {
+    HOST.with_borrow_mut(|host| host.fps = Some(fps));
+}
+
+/// The frame whose scripted buttons are reported, starting from 0.
+pub fn frame() -> usize {
+    HOST.with_borrow(|host| host.frame)
+}
+
+/// Moves on to the next frame's buttons. Call after `update` and `render`.
+pub fn next_frame() {
+    HOST.with_borrow_mut(|host| host.frame += 1);
+}
+
+/// Returns the commands recorded since the last call.
+pub fn take_commands() -> Vec<HostCommand> {
+    HOST.with_borrow_mut(|host| std::mem::take(&mut host.commands))
+}
+
+fn record(command: HostCommand) {
+    HOST.with_borrow_mut(|host| host.commands.push(command));
+}
+
+unsafe fn read<T>(ptr: *const u8) -> T {
+    unsafe { (ptr as *const T).read_unaligned() }
+}
+
+fn held(player_id: i32, button: Button) -> i32 {
+    HOST.with_borrow(|host| host.held(player_id) & button.bit() != 0) as i32
+}
+
+fn pressed(player_id: i32, button: Button) -> i32 {
+    HOST.with_borrow(|host| {
+        host.held(player_id) & !host.previously_held(player_id) & button.bit() != 0
+    }) as i32
+}
+
+fn released(player_id: i32, button: Button) -> i32 {
+    HOST.with_borrow(|host| {
+        !host.held(player_id) & host.previously_held(player_id) & button.bit() != 0
+    }) as i32
+}
+
+/// Defines the pressed, released and held imports for each button.
+macro_rules! buttons {
+    ($($button:ident: $pressed:ident, $released:ident, $held:ident;)*) => {
+        $(
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn $pressed(player_id: i32) -> i32 {
+                pressed(player_id, Button::$button)
+            }
+
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn $released(player_id: i32) -> i32 {
+                released(player_id, Button::$button)
+            }
+
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn $held(player_id: i32) -> i32 {
+                held(player_id, Button::$button)
+            }
+        )*
+    };
+}
+
+/// Defines imports for inputs which the mock host never reports.
+macro_rules! unused_inputs {
+    ($($name:ident -> $ty:ty;)*) => {
+        $(
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn $name(_player_id: i32) -> $ty {
+                Default::default()
+            }
+        )*
+    };
+}
+
+// Data
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn height() -> i32 {
+    HEIGHT
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn width() -> i32 {
+    WIDTH
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fps() -> i32 {
+    HOST.with_borrow(|host| host.fps.unwrap_or(FPS))
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn frame_time() -> f32 {
+    1.0 / FPS as f32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn push_audio(
+    _buffer_ptr: *const u8,
+    buffer_len: i32,
+    channel_count: i32,
+    sample_rate: i32,
+) {
+    record(HostCommand::PushAudio {
+        len: buffer_len,
+        channel_count,
+        sample_rate,
+    });
+}
+
+// Draw 3d
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn draw_tri_list(_data_ptr: *const u8, len: i32, pipeline: i32) {
+    record(HostCommand::DrawTriList { len, pipeline });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn draw_tri_list_indexed(
+    _data_ptr: *const u8,
+    data_len: i32,
+    _index_ptr: *const u8,
+    index_len: i32,
+    pipeline: i32,
+) {
+    record(HostCommand::DrawTriListIndexed {
+        len: data_len,
+        index_len,
+        pipeline,
+    });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn push_model_matrix(mat_ptr: *const u8) {
+    record(HostCommand::ModelMatrix(unsafe { read(mat_ptr) }));
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn push_proj_matrix(proj_ptr: *const u8) {
+    record(HostCommand::ProjMatrix(unsafe { read(proj_ptr) }));
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn push_view_matrix_pos(view_ptr: *const u8, pos_ptr: *const u8) {
+    record(HostCommand::ViewMatrix {
+        view: unsafe { read(view_ptr) },
+        pos: unsafe { read(pos_ptr) },
+    });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn draw_static_mesh(id: i32) {
+    record(HostCommand::DrawStaticMesh(id));
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn draw_static_mesh_indexed(id: i32) {
+    record(HostCommand::DrawStaticMeshIndexed(id));
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn draw_sprite(id: i32) {
+    record(HostCommand::DrawSprite(id));
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_texture(id: i32, layer: i32, blend: i32) {
+    record(HostCommand::SetTexture { id, layer, blend });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_matcap(id: i32, layer: i32, blend: i32) {
+    record(HostCommand::SetMatcap { id, layer, blend });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_winding_order(clockwise: i32) {
+    record(HostCommand::SetWindingOrder(clockwise));
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn load_texture(
+    _data_ptr: *const u8,
+    width: i32,
+    height: i32,
+    has_alpha: i32,
+) -> i32 {
+    let id = HOST.with_borrow_mut(|host| {
+        host.textures += 1;
+        host.textures - 1
+    });
+    record(HostCommand::LoadTexture {
+        id,
+        width,
+        height,
+        has_alpha,
+    });
+    id
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn load_static_mesh(
+    _data_ptr: *const u8,
+    data_len: i32,
+    pipeline: i32,
+) -> i32 {
+    let id = next_mesh_id();
+    record(HostCommand::LoadStaticMesh {
+        id,
+        len: data_len,
+        pipeline,
+    });
+    id
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn load_static_mesh_indexed(
+    _data_ptr: *const u8,
+    data_len: i32,
+    _index_ptr: *const u8,
+    index_len: i32,
+    pipeline: i32,
+) -> i32 {
+    let id = next_mesh_id();
+    record(HostCommand::LoadStaticMeshIndexed {
+        id,
+        len: data_len,
+        index_len,
+        pipeline,
+    });
+    id
+}
+
+/// Meshes share ids between the indexed and non-indexed loads.
+fn next_mesh_id() -> i32 {
+    HOST.with_borrow_mut(|host| {
+        host.meshes += 1;
+        host.meshes - 1
+    })
+}
+
+// Input
+
+buttons! {
+    A: button_a_pressed, button_a_released, button_a_held;
+    B: button_b_pressed, button_b_released, button_b_held;
+    C: button_c_pressed, button_c_released, button_c_held;
+    D: button_d_pressed, button_d_released, button_d_held;
+    Up: button_up_pressed, button_up_released, button_up_held;
+    Down: button_down_pressed, button_down_released, button_down_held;
+    Left: button_left_pressed, button_left_released, button_left_held;
+    Right: button_right_pressed, button_right_released, button_right_held;
+    Start: button_start_pressed, button_start_released, button_start_held;
+    Select: button_select_pressed, button_select_released, button_select_held;
+    LeftShoulder:
+        button_left_shoulder_pressed, button_left_shoulder_released, button_left_shoulder_held;
+    RightShoulder:
+        button_right_shoulder_pressed, button_right_shoulder_released, button_right_shoulder_held;
+    LeftStick: button_left_stick_pressed, button_left_stick_released, button_left_stick_held;
+    RightStick: button_right_stick_pressed, button_right_stick_released, button_right_stick_held;
+    LeftTrigger:
+        button_left_trigger_pressed, button_left_trigger_released, button_left_trigger_held;
+    RightTrigger:
+        button_right_trigger_pressed, button_right_trigger_released, button_right_trigger_held;
+}
+
+unused_inputs! {
+    analog_left_x -> f32;
+    analog_left_y -> f32;
+    analog_right_x -> f32;
+    analog_right_y -> f32;
+    trigger_left -> f32;
+    trigger_right -> f32;
+
+    mouse_left_pressed -> i32;
+    mouse_left_released -> i32;
+    mouse_left_held -> i32;
+    mouse_right_pressed -> i32;
+    mouse_right_released -> i32;
+    mouse_right_held -> i32;
+    mouse_middle_pressed -> i32;
+    mouse_middle_released -> i32;
+    mouse_middle_held -> i32;
+
+    mouse_x_pos -> i32;
+    mouse_y_pos -> i32;
+    mouse_x_delta -> i32;
+    mouse_y_delta -> i32;
+
+    mouse_wheel_up -> i32;
+    mouse_wheel_down -> i32;
+    mouse_wheel_left -> i32;
+    mouse_wheel_right -> i32;
+
+    raw_mouse_state -> i64;
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lock_mouse(_locked: i32) {}
+
+/// The held buttons as bits, in the order of `Button`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn raw_input_state(player_id: i32) -> i64 {
+    HOST.with_borrow(|host| host.held(player_id)) as i64
+}
+
+// Text
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn console_log(text_ptr: *const u8, len: i32) {
+    let bytes = unsafe { std::slice::from_raw_parts(text_ptr, len as usize) };
+    record(HostCommand::Log(
+        String::from_utf8_lossy(bytes).into_owned(),
+    ));
+}
+
+/// Takes the length in UTF-16 code units.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn console_log_utf16(text_ptr: *const u8, len: i32) {
+    let units: Vec<u16> = (0..len as usize)
+        .map(|i| unsafe { read(text_ptr.add(i * 2)) })
+        .collect();
+    record(HostCommand::Log(String::from_utf16_lossy(&units)));
+}
+
+// Random
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_seed(seed: i32) {
+    HOST.with_borrow_mut(|host| host.random = seed as u64);
+    record(HostCommand::SetSeed(seed));
+}
+
+/// Returns a number from `min` up to but not including `max`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn random_int_range(min: i32, max: i32) -> i32 {
+    let range = (max as i64 - min as i64).max(1) as u64;
+    let offset = HOST.with_borrow_mut(|host| host.next_random()) % range;
+    (min as i64 + offset as i64) as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn random_float() -> f32 {
+    (HOST.with_borrow_mut(|host| host.next_random()) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn random_float_range(min: f32, max: f32) -> f32 {
+    min + unsafe { random_float() } * (max - min)
+}
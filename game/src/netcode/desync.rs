@@ -0,0 +1,82 @@
+use std::collections::VecDeque;
+
+/// Compares the checksums of each confirmed frame with the remote player's,
+/// to catch the simulations playing out differently. Checksum `n` is of the
+/// match after frame `n` was simulated.
+#[derive(Default)]
+pub struct DesyncDetector {
+    /// The first frame which hasn't been compared yet.
+    compared_frame: usize,
+    /// Local checksums from `local_start` onwards, kept until they've been
+    /// both compared and acknowledged by the remote player.
+    local_checksums: VecDeque<u64>,
+    local_start: usize,
+    /// Remote checksums from `compared_frame` onwards.
+    remote_checksums: VecDeque<u64>,
+    /// How many local checksums the remote player has received.
+    remote_ack: usize,
+    desync_frame: Option<usize>,
+}
+
+impl DesyncDetector {
+    /// The first frame which played out differently for the remote player.
+    pub fn desync_frame(&self) -> Option<usize> {
+        self.desync_frame
+    }
+
+    /// How many of the remote player's checksums have been received,
+    /// counting from frame 0.
+    pub fn received(&self) -> usize {
+        self.compared_frame + self.remote_checksums.len()
+    }
+
+    /// Adds the checksum of the next frame to be confirmed locally.
+    pub fn add_local(&mut self, checksum: u64) {
+        self.local_checksums.push_back(checksum);
+        self.compare();
+    }
+
+    /// Adds checksums from a packet starting at `start_frame`, skipping any
+    /// which have been received already.
+    pub fn add_remote(&mut self, ack: usize, start_frame: usize, checksums: &[u64]) {
+        self.remote_ack = self.remote_ack.max(ack);
+
+        let received = self.received();
+        if start_frame <= received {
+            let new_checksums = checksums.iter().skip(received - start_frame);
+            self.remote_checksums.extend(new_checksums);
+        }
+
+        self.compare();
+    }
+
+    /// The first frame of `unacked`.
+    pub fn unacked_start(&self) -> usize {
+        self.remote_ack.max(self.local_start)
+    }
+
+    /// Local checksums the remote player hasn't acknowledged yet.
+    pub fn unacked(&self) -> impl Iterator<Item = &u64> {
+        self.local_checksums
+            .iter()
+            .skip(self.unacked_start() - self.local_start)
+    }
+
+    fn compare(&mut self) {
+        let local_end = self.local_start + self.local_checksums.len();
+        while self.compared_frame < local_end
+            && let Some(remote) = self.remote_checksums.pop_front()
+        {
+            let local = self.local_checksums[self.compared_frame - self.local_start];
+            if local != remote && self.desync_frame.is_none() {
+                self.desync_frame = Some(self.compared_frame);
+            }
+            self.compared_frame += 1;
+        }
+
+        while self.local_start < self.compared_frame.min(self.remote_ack) {
+            self.local_checksums.pop_front();
+            self.local_start += 1;
+        }
+    }
+}
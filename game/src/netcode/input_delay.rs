@@ -0,0 +1,32 @@
+use std::collections::VecDeque;
+
+use crate::gameplay::InputState;
+
+/// Holds inputs back for a fixed number of frames before they reach the
+/// input buffer. Delaying the local player's inputs gives them time to reach
+/// the remote player before they're needed, so fewer frames are predicted.
+pub struct InputDelay {
+    queue: VecDeque<InputState>,
+}
+
+impl InputDelay {
+    /// Starts with `frames` neutral inputs queued, for the first frames of
+    /// the match before any delayed inputs are ready.
+    pub fn new(frames: usize) -> Self {
+        Self {
+            queue: std::iter::repeat_n(InputState::default(), frames).collect(),
+        }
+    }
+
+    /// Queues the input sampled this frame, returning the one sampled
+    /// `frames` frames ago to be used now.
+    pub fn push(&mut self, input: InputState) -> InputState {
+        self.queue.push_back(input);
+        self.queue.pop_front().unwrap()
+    }
+
+    /// The inputs waiting to be used, oldest first.
+    pub fn queued(&self) -> impl Iterator<Item = &InputState> {
+        self.queue.iter()
+    }
+}
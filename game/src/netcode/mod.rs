@@ -0,0 +1,10 @@
+mod desync;
+mod input_delay;
+mod packet;
+mod session;
+mod time_sync;
+mod transport;
+
+pub use input_delay::*;
+pub use session::*;
+pub use transport::*;
@@ -0,0 +1,83 @@
+use crate::{byte_reader::ByteReader, gameplay::InputState};
+
+/// Sent every frame, carrying all of the sender's inputs and checksums
+/// which the receiver hasn't acknowledged yet, so that lost packets are made
+/// up for by the next one to arrive.
+pub struct InputPacket {
+    /// How many of the receiver's inputs the sender has, counting from frame 0.
+    pub ack: usize,
+    /// The frame of the first input.
+    pub start_frame: usize,
+    pub inputs: Vec<InputState>,
+    /// The sender's next frame to simulate.
+    pub frame: usize,
+    /// How far the sender is ahead of the last frame it received from the
+    /// receiver.
+    pub advantage: isize,
+    /// How many of the receiver's checksums the sender has.
+    pub checksum_ack: usize,
+    /// The frame of the first checksum.
+    pub checksum_start_frame: usize,
+    pub checksums: Vec<u64>,
+}
+
+impl InputPacket {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_u32(&mut bytes, self.ack);
+        write_u32(&mut bytes, self.start_frame);
+        write_u32(&mut bytes, self.inputs.len());
+        bytes.extend(self.inputs.iter().map(InputState::to_byte));
+
+        write_u32(&mut bytes, self.frame);
+        bytes.extend_from_slice(&(self.advantage as i32).to_le_bytes());
+
+        write_u32(&mut bytes, self.checksum_ack);
+        write_u32(&mut bytes, self.checksum_start_frame);
+        write_u32(&mut bytes, self.checksums.len());
+        for checksum in &self.checksums {
+            bytes.extend_from_slice(&checksum.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Returns None for packets which are cut short or hold invalid inputs.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = ByteReader::new(bytes);
+
+        let ack = reader.read_u32()? as usize;
+        let start_frame = reader.read_u32()? as usize;
+        let input_count = reader.read_u32()? as usize;
+        let inputs = reader
+            .read_bytes(input_count)?
+            .iter()
+            .map(|byte| InputState::from_byte(*byte))
+            .collect::<Option<_>>()?;
+
+        let frame = reader.read_u32()? as usize;
+        let advantage = reader.read_i32()? as isize;
+
+        let checksum_ack = reader.read_u32()? as usize;
+        let checksum_start_frame = reader.read_u32()? as usize;
+        let checksum_count = reader.read_u32()?;
+        let checksums = (0..checksum_count)
+            .map(|_| reader.read_u64())
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            ack,
+            start_frame,
+            inputs,
+            frame,
+            advantage,
+            checksum_ack,
+            checksum_start_frame,
+            checksums,
+        })
+    }
+}
+
+fn write_u32(bytes: &mut Vec<u8>, value: usize) {
+    bytes.extend_from_slice(&(value as u32).to_le_bytes());
+}
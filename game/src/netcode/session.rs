@@ -0,0 +1,548 @@
+use std::collections::VecDeque;
+
+use crate::gameplay::{InputState, MatchSnapshot, MatchState};
+
+use super::{
+    InputDelay, Transport, desync::DesyncDetector, packet::InputPacket, time_sync::TimeSync,
+};
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Side {
+    Player1,
+    Player2,
+}
+
+pub struct SessionSettings {
+    /// The most frames which may be simulated with a predicted remote input.
+    /// The session stalls rather than getting any further ahead.
+    pub max_rollback_frames: usize,
+    /// Frames between sampling the local player's input and using it.
+    pub input_delay: usize,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            max_rollback_frames: 8,
+            input_delay: 2,
+        }
+    }
+}
+
+/// A frame which was simulated before the remote player's input for it was
+/// confirmed, so may need simulating again.
+struct PendingFrame {
+    /// The match as it was before the frame.
+    snapshot: MatchSnapshot,
+    local_input: InputState,
+    /// The remote input the frame was last simulated with.
+    remote_input: InputState,
+}
+
+/// Runs a match against a remote player without waiting for their inputs.
+/// Until the remote player's input for a frame arrives, they're predicted to
+/// still be holding the last input received. If the prediction turns out to
+/// be wrong, the match is rolled back to that frame and simulated again.
+pub struct RollbackSession<T: Transport> {
+    match_state: MatchState,
+    transport: T,
+    local_side: Side,
+    settings: SessionSettings,
+    /// The next frame to simulate.
+    frame: usize,
+    /// The first frame simulated with a predicted remote input. Every frame
+    /// from here up to `frame` is pending.
+    confirmed_frame: usize,
+    pending: VecDeque<PendingFrame>,
+    /// Remote inputs received from `confirmed_frame` onwards.
+    remote_inputs: VecDeque<InputState>,
+    /// The latest remote input received, which is used as the prediction.
+    last_remote_input: InputState,
+    /// Local inputs waiting out the input delay before they're simulated.
+    input_delay: InputDelay,
+    /// Local inputs from `remote_ack` onwards, which the remote player hasn't
+    /// acknowledged receiving yet. These run ahead of `frame` by the input
+    /// delay.
+    unacked_inputs: VecDeque<InputState>,
+    remote_ack: usize,
+    time_sync: TimeSync,
+    desync: DesyncDetector,
+}
+
+impl<T: Transport> RollbackSession<T> {
+    pub fn new(
+        match_state: MatchState,
+        local_side: Side,
+        transport: T,
+        settings: SessionSettings,
+    ) -> Self {
+        let input_delay = InputDelay::new(settings.input_delay);
+
+        Self {
+            match_state,
+            transport,
+            local_side,
+            frame: 0,
+            confirmed_frame: 0,
+            pending: VecDeque::with_capacity(settings.max_rollback_frames),
+            remote_inputs: VecDeque::new(),
+            last_remote_input: InputState::default(),
+            unacked_inputs: input_delay.queued().copied().collect(),
+            input_delay,
+            remote_ack: 0,
+            time_sync: TimeSync::default(),
+            desync: DesyncDetector::default(),
+            settings,
+        }
+    }
+
+    pub fn match_state(&self) -> &MatchState {
+        &self.match_state
+    }
+
+    /// The next frame to be simulated.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Frames before this have been simulated with both players' real inputs,
+    /// and won't be rolled back any more.
+    pub fn confirmed_frame(&self) -> usize {
+        self.confirmed_frame
+    }
+
+    /// Roughly how many frames ahead of the remote player this side is.
+    pub fn frames_ahead(&self) -> isize {
+        self.time_sync.frames_ahead(self.frame)
+    }
+
+    /// The first frame which played out differently for the remote player,
+    /// meaning the two simulations have drifted apart.
+    pub fn desync_frame(&self) -> Option<usize> {
+        self.desync.desync_frame()
+    }
+
+    /// Samples the local player's input and simulates the next frame with
+    /// the input from the start of the input delay, rolling back first if
+    /// any remote inputs have arrived which weren't predicted.
+    ///
+    /// Returns false without simulating if the frame would go past the
+    /// rollback window, or to let the remote player catch up when this side
+    /// has got ahead of them.
+    pub fn advance_frame(&mut self, local_input: InputState) -> bool {
+        self.receive_packets();
+        if self.frame + 1 > self.remote_received() + self.settings.max_rollback_frames
+            || self.time_sync.should_stall(self.frame)
+        {
+            self.send_packet();
+            return false;
+        }
+
+        self.unacked_inputs.push_back(local_input);
+        let local_input = self.input_delay.push(local_input);
+        self.send_packet();
+
+        let remote_input = self.remote_input(self.frame);
+        self.pending.push_back(PendingFrame {
+            snapshot: self.match_state.save_state(),
+            local_input,
+            remote_input,
+        });
+        self.simulate(local_input, remote_input);
+        self.frame += 1;
+        self.confirm();
+
+        true
+    }
+
+    /// Handles any packets which have arrived and resends unacknowledged
+    /// inputs, without simulating a new frame. Used while waiting for the
+    /// remote player to finish.
+    pub fn poll(&mut self) {
+        self.receive_packets();
+        self.send_packet();
+    }
+
+    /// How many of the remote player's inputs have been received, counting
+    /// from frame 0.
+    fn remote_received(&self) -> usize {
+        self.confirmed_frame + self.remote_inputs.len()
+    }
+
+    /// The remote input for the frame if it has been received, otherwise the
+    /// prediction.
+    fn remote_input(&self, frame: usize) -> InputState {
+        self.remote_inputs
+            .get(frame - self.confirmed_frame)
+            .copied()
+            .unwrap_or(self.last_remote_input)
+    }
+
+    fn receive_packets(&mut self) {
+        while let Some(bytes) = self.transport.receive() {
+            if let Some(packet) = InputPacket::decode(&bytes) {
+                self.time_sync.update(packet.frame, packet.advantage);
+                self.desync.add_remote(
+                    packet.checksum_ack,
+                    packet.checksum_start_frame,
+                    &packet.checksums,
+                );
+                self.add_remote_inputs(packet);
+            }
+        }
+
+        self.rollback();
+        self.confirm();
+    }
+
+    fn add_remote_inputs(&mut self, packet: InputPacket) {
+        while self.remote_ack < packet.ack && !self.unacked_inputs.is_empty() {
+            self.unacked_inputs.pop_front();
+            self.remote_ack += 1;
+        }
+
+        // Packets which leave a gap after the inputs already received are
+        // out of order, and the inputs will come again in a later packet.
+        let received = self.remote_received();
+        if packet.start_frame > received {
+            return;
+        }
+
+        let new_inputs = packet
+            .inputs
+            .into_iter()
+            .skip(received - packet.start_frame);
+        for input in new_inputs {
+            self.remote_inputs.push_back(input);
+            self.last_remote_input = input;
+        }
+    }
+
+    /// Goes back to the first pending frame whose prediction was wrong, and
+    /// simulates every frame from there again with the inputs now known.
+    fn rollback(&mut self) {
+        let Some(start) = self
+            .pending
+            .iter()
+            .zip(&self.remote_inputs)
+            .position(|(pending, input)| pending.remote_input != *input)
+        else {
+            return;
+        };
+
+        self.match_state.load_state(&self.pending[start].snapshot);
+        for index in start..self.pending.len() {
+            let remote_input = self.remote_input(self.confirmed_frame + index);
+            if index > start {
+                self.pending[index].snapshot = self.match_state.save_state();
+            }
+            self.pending[index].remote_input = remote_input;
+
+            self.simulate(self.pending[index].local_input, remote_input);
+        }
+    }
+
+    /// Stops tracking pending frames once their remote inputs have been
+    /// received, checking the match against the remote player's as it was
+    /// after each. Must be run after `rollback`, so that they were simulated
+    /// with the received inputs.
+    fn confirm(&mut self) {
+        while !self.pending.is_empty() && !self.remote_inputs.is_empty() {
+            self.pending.pop_front();
+            self.remote_inputs.pop_front();
+            self.confirmed_frame += 1;
+
+            let checksum = match self.pending.front() {
+                Some(next) => next.snapshot.checksum(),
+                None => self.match_state.checksum(),
+            };
+            self.desync.add_local(checksum);
+        }
+    }
+
+    fn send_packet(&mut self) {
+        let packet = InputPacket {
+            ack: self.remote_received(),
+            start_frame: self.remote_ack,
+            inputs: self.unacked_inputs.iter().copied().collect(),
+            frame: self.frame,
+            advantage: self.time_sync.local_advantage(self.frame),
+            checksum_ack: self.desync.received(),
+            checksum_start_frame: self.desync.unacked_start(),
+            checksums: self.desync.unacked().copied().collect(),
+        };
+        self.transport.send(&packet.encode());
+    }
+
+    fn simulate(&mut self, local_input: InputState, remote_input: InputState) {
+        match self.local_side {
+            Side::Player1 => self.match_state.update(local_input, remote_input),
+            Side::Player2 => self.match_state.update(remote_input, local_input),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+    use crate::{
+        db::DEFAULT_CHARACTER,
+        gameplay::{
+            InputState, MatchSettings, MatchState,
+            testing::{SCRIPTED_FRAMES, scripted_inputs},
+        },
+    };
+
+    use super::{InputPacket, RollbackSession, SessionSettings, Side, Transport};
+
+    /// Both directions of a connection, delivering packets `latency` ticks
+    /// after they're sent, and losing every `drop_every`th packet sent each
+    /// way. Player 1's input for `tamper_frame` is corrupted on the way.
+    #[derive(Default)]
+    struct Network {
+        now: usize,
+        latency: usize,
+        drop_every: Option<usize>,
+        tamper_frame: Option<usize>,
+        sent: [usize; 2],
+        in_flight: [VecDeque<(usize, Vec<u8>)>; 2],
+    }
+
+    struct TestTransport {
+        network: Rc<RefCell<Network>>,
+        index: usize,
+    }
+
+    impl Transport for TestTransport {
+        fn send(&mut self, packet: &[u8]) {
+            let mut network = self.network.borrow_mut();
+            network.sent[self.index] += 1;
+            let sent = network.sent[self.index];
+            if network
+                .drop_every
+                .is_some_and(|drop_every| sent.is_multiple_of(drop_every))
+            {
+                return;
+            }
+
+            let mut packet = packet.to_vec();
+            if let Some(frame) = network.tamper_frame
+                && self.index == 0
+            {
+                let mut decoded = InputPacket::decode(&packet).unwrap();
+                if let Some(input) = frame
+                    .checked_sub(decoded.start_frame)
+                    .and_then(|index| decoded.inputs.get_mut(index))
+                {
+                    input.a_pressed = !input.a_pressed;
+                }
+                packet = decoded.encode();
+            }
+
+            let arrival = network.now + network.latency;
+            network.in_flight[1 - self.index].push_back((arrival, packet));
+        }
+
+        fn receive(&mut self) -> Option<Vec<u8>> {
+            let mut network = self.network.borrow_mut();
+            let now = network.now;
+            let queue = &mut network.in_flight[self.index];
+            queue
+                .front()
+                .is_some_and(|(arrival, _)| *arrival <= now)
+                .then(|| queue.pop_front().unwrap().1)
+        }
+    }
+
+    fn scripted_input(side: Side, frame: usize) -> InputState {
+        let (player_1, player_2) = scripted_inputs(frame);
+        match side {
+            Side::Player1 => player_1,
+            Side::Player2 => player_2,
+        }
+    }
+
+    fn new_match() -> MatchState {
+        MatchState::new(
+            &DEFAULT_CHARACTER,
+            &DEFAULT_CHARACTER,
+            MatchSettings::default(),
+        )
+    }
+
+    /// Plays the scripted inputs offline, held back by the input delay.
+    fn local_checksum(input_delay: usize) -> u64 {
+        let delayed = |side, frame: usize| {
+            frame
+                .checked_sub(input_delay)
+                .map_or(InputState::default(), |frame| scripted_input(side, frame))
+        };
+
+        let mut state = new_match();
+        for frame in 0..SCRIPTED_FRAMES {
+            state.update(delayed(Side::Player1, frame), delayed(Side::Player2, frame));
+        }
+        state.checksum()
+    }
+
+    fn connect(network: Network) -> (TestTransport, TestTransport, Rc<RefCell<Network>>) {
+        let network = Rc::new(RefCell::new(network));
+        let transport = |index| TestTransport {
+            network: network.clone(),
+            index,
+        };
+
+        (transport(0), transport(1), network)
+    }
+
+    fn sessions(
+        network: Network,
+        settings: fn() -> SessionSettings,
+    ) -> ([RollbackSession<TestTransport>; 2], Rc<RefCell<Network>>) {
+        let (transport_1, transport_2, network) = connect(network);
+        let sessions = [
+            RollbackSession::new(new_match(), Side::Player1, transport_1, settings()),
+            RollbackSession::new(new_match(), Side::Player2, transport_2, settings()),
+        ];
+
+        (sessions, network)
+    }
+
+    /// Ticks both sides once, advancing them until they reach `SCRIPTED_FRAMES`.
+    fn tick(sessions: &mut [RollbackSession<TestTransport>; 2], network: &RefCell<Network>) {
+        for session in sessions.iter_mut() {
+            if session.frame() < SCRIPTED_FRAMES {
+                session.advance_frame(scripted_input(session.local_side, session.frame()));
+            } else {
+                session.poll();
+            }
+
+            let pending = session.frame() - session.confirmed_frame();
+            assert!(pending <= session.settings.max_rollback_frames);
+        }
+        network.borrow_mut().now += 1;
+    }
+
+    /// Plays `SCRIPTED_FRAMES` frames on both sides, then waits for all of the
+    /// inputs to be confirmed and returns each side's checksum.
+    fn play(network: Network, settings: fn() -> SessionSettings) -> [u64; 2] {
+        let (mut sessions, network) = sessions(network, settings);
+
+        for _ in 0..SCRIPTED_FRAMES * 10 {
+            if sessions
+                .iter()
+                .all(|session| session.confirmed_frame() == SCRIPTED_FRAMES)
+            {
+                break;
+            }
+            tick(&mut sessions, &network);
+        }
+
+        sessions.map(|session| {
+            assert_eq!(session.confirmed_frame(), SCRIPTED_FRAMES);
+            assert_eq!(session.desync_frame(), None);
+            session.match_state().checksum()
+        })
+    }
+
+    #[test]
+    fn loopback_matches_local_simulation() {
+        let expected = local_checksum(2);
+        assert_eq!(
+            play(Network::default(), SessionSettings::default),
+            [expected; 2]
+        );
+    }
+
+    #[test]
+    fn lossy_connection_rolls_back_to_match_local_simulation() {
+        let no_delay = || SessionSettings {
+            input_delay: 0,
+            ..SessionSettings::default()
+        };
+        let expected = local_checksum(0);
+
+        let network = Network {
+            latency: 4,
+            drop_every: Some(3),
+            ..Network::default()
+        };
+        assert_eq!(play(network, no_delay), [expected; 2]);
+
+        // Longer than the rollback window, so both sides stall as well.
+        let network = Network {
+            latency: 12,
+            drop_every: Some(2),
+            ..Network::default()
+        };
+        assert_eq!(play(network, no_delay), [expected; 2]);
+    }
+
+    #[test]
+    fn input_delay_covering_latency_avoids_prediction() {
+        let delay = || SessionSettings {
+            input_delay: 3,
+            ..SessionSettings::default()
+        };
+        let network = Network {
+            latency: 3,
+            ..Network::default()
+        };
+        let (mut sessions, network) = sessions(network, delay);
+
+        // The first packets take the latency to arrive, so the opening
+        // frames are still predicted.
+        while sessions[1].frame() < SCRIPTED_FRAMES {
+            tick(&mut sessions, &network);
+            for session in &sessions {
+                if session.frame() > 3 {
+                    assert_eq!(session.confirmed_frame(), session.frame());
+                }
+            }
+        }
+        assert_eq!(sessions[1].match_state().checksum(), local_checksum(3));
+    }
+
+    #[test]
+    fn side_which_is_ahead_waits_for_the_other() {
+        let network = Network {
+            latency: 2,
+            ..Network::default()
+        };
+        let (mut sessions, network) = sessions(network, SessionSettings::default);
+
+        // Player 2 hasn't started yet, so player 1 stalls well before
+        // reaching the rollback window.
+        for frame in 0..30 {
+            sessions[0].advance_frame(scripted_input(Side::Player1, frame));
+            network.borrow_mut().now += 1;
+        }
+        assert!(sessions[0].frame() < 4);
+
+        for _ in 0..100 {
+            tick(&mut sessions, &network);
+        }
+        for _ in 0..100 {
+            tick(&mut sessions, &network);
+            assert!(sessions[0].frame().abs_diff(sessions[1].frame()) <= 1);
+        }
+    }
+
+    #[test]
+    fn reports_first_desynced_frame() {
+        let network = Network {
+            latency: 2,
+            tamper_frame: Some(250),
+            ..Network::default()
+        };
+        let (mut sessions, network) = sessions(network, SessionSettings::default);
+
+        for _ in 0..SCRIPTED_FRAMES {
+            tick(&mut sessions, &network);
+        }
+        for session in &sessions {
+            assert_eq!(session.desync_frame(), Some(250));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+/// Frames ahead of the remote player a side may get before it stalls.
+const MAX_FRAMES_AHEAD: isize = 0;
+
+/// Keeps both sides' frame counters level, so that neither has to predict
+/// much more than the other. Each side sees the other as behind by the
+/// latency, so the advantages are compared rather than the frames.
+#[derive(Default)]
+pub struct TimeSync {
+    /// The latest frame the remote player has reported.
+    remote_frame: usize,
+    /// How far the remote player saw themselves ahead of this side.
+    remote_advantage: isize,
+}
+
+impl TimeSync {
+    /// Records the remote player's frame and advantage from a packet,
+    /// ignoring packets older than the latest.
+    pub fn update(&mut self, remote_frame: usize, remote_advantage: isize) {
+        if remote_frame >= self.remote_frame {
+            self.remote_frame = remote_frame;
+            self.remote_advantage = remote_advantage;
+        }
+    }
+
+    /// How far ahead of the latest frame received from the remote player
+    /// the local frame is.
+    pub fn local_advantage(&self, frame: usize) -> isize {
+        frame as isize - self.remote_frame as isize
+    }
+
+    /// Roughly how many frames ahead of the remote player this side is. The
+    /// latency is in both advantages, so half their difference cancels it out.
+    pub fn frames_ahead(&self, frame: usize) -> isize {
+        (self.local_advantage(frame) - self.remote_advantage) / 2
+    }
+
+    /// Whether to skip the frame and let the remote player catch up.
+    pub fn should_stall(&self, frame: usize) -> bool {
+        self.frames_ahead(frame) > MAX_FRAMES_AHEAD
+    }
+}
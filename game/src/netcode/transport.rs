@@ -0,0 +1,9 @@
+/// A connection to the remote player. Packets may be lost, duplicated or
+/// arrive out of order, so anything sent over it must be safe to resend.
+pub trait Transport {
+    fn send(&mut self, packet: &[u8]);
+
+    /// Returns the next packet which has arrived, or None once there are
+    /// no more waiting.
+    fn receive(&mut self) -> Option<Vec<u8>>;
+}
@@ -0,0 +1,290 @@
+use crate::{
+    byte_reader::ByteReader,
+    db::{CharacterDefinition, find_character},
+    gameplay::{InputState, MatchSettings},
+};
+
+const MAGIC: &[u8; 4] = b"FFRP";
+/// Bumped whenever the layout changes, or the simulation changes in a way
+/// which makes older replays play out differently.
+pub const REPLAY_VERSION: u16 = 2;
+
+/// Everything needed to play a match again: the characters, the seed the
+/// host's random numbers were seeded with, the match settings and both
+/// players' inputs for every frame.
+///
+/// Encoded as the magic bytes `FFRP` and the version, then the header, then
+/// the inputs as runs of frames where neither player's inputs changed. All
+/// numbers are little endian, with sizes and settings written as 64 bits so
+/// that none are cut short.
+#[derive(Clone)]
+pub struct Replay {
+    pub player_1: &'static CharacterDefinition,
+    pub player_2: &'static CharacterDefinition,
+    pub seed: i32,
+    pub settings: MatchSettings,
+    pub(super) runs: Vec<InputRun>,
+}
+
+/// A span of frames where both players held the same inputs.
+#[derive(Clone, Copy)]
+pub(super) struct InputRun {
+    pub player_1: InputState,
+    pub player_2: InputState,
+    pub frames: u16,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ReplayError {
+    NotAReplay,
+    UnsupportedVersion(u16),
+    UnknownCharacter(String),
+    /// The replay ended early or holds values which can't be decoded.
+    Corrupt,
+}
+
+impl Replay {
+    pub fn new(
+        player_1: &'static CharacterDefinition,
+        player_2: &'static CharacterDefinition,
+        seed: i32,
+        settings: MatchSettings,
+    ) -> Self {
+        Self {
+            player_1,
+            player_2,
+            seed,
+            settings,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Adds the next frame's inputs.
+    pub fn record(&mut self, player_1: InputState, player_2: InputState) {
+        self.record_frames(player_1, player_2, 1);
+    }
+
+    /// Adds the same inputs for the next `frames` frames.
+    pub fn record_frames(&mut self, player_1: InputState, player_2: InputState, mut frames: usize) {
+        if let Some(run) = self.runs.last_mut()
+            && run.player_1 == player_1
+            && run.player_2 == player_2
+        {
+            let added = frames.min((u16::MAX - run.frames) as usize);
+            run.frames += added as u16;
+            frames -= added;
+        }
+
+        while frames > 0 {
+            let added = frames.min(u16::MAX as usize);
+            self.runs.push(InputRun {
+                player_1,
+                player_2,
+                frames: added as u16,
+            });
+            frames -= added;
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.runs.iter().map(|run| run.frames as usize).sum()
+    }
+
+    /// Both players' inputs for each frame in order.
+    pub fn inputs(&self) -> impl Iterator<Item = (InputState, InputState)> + '_ {
+        self.runs
+            .iter()
+            .flat_map(|run| std::iter::repeat_n((run.player_1, run.player_2), run.frames as usize))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64 + self.runs.len() * 4);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+
+        for character in [self.player_1, self.player_2] {
+            write_usize(&mut bytes, character.name.len());
+            bytes.extend_from_slice(character.name.as_bytes());
+        }
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        write_usize(&mut bytes, self.settings.rounds_to_win);
+        // A flag for whether there's a time limit, then the limit if so.
+        match self.settings.round_seconds {
+            Some(round_seconds) => {
+                bytes.push(1);
+                write_usize(&mut bytes, round_seconds);
+            }
+            None => bytes.push(0),
+        }
+        write_usize(&mut bytes, self.settings.frames_per_second);
+
+        write_usize(&mut bytes, self.runs.len());
+        for run in &self.runs {
+            bytes.push(run.player_1.to_byte());
+            bytes.push(run.player_2.to_byte());
+            bytes.extend_from_slice(&run.frames.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.read_array::<4>().as_ref() != Some(MAGIC) {
+            return Err(ReplayError::NotAReplay);
+        }
+
+        let version = reader.read_u16().ok_or(ReplayError::Corrupt)?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let player_1 = read_character(&mut reader)?;
+        let player_2 = read_character(&mut reader)?;
+        Self::decode_body(&mut reader, player_1, player_2).ok_or(ReplayError::Corrupt)
+    }
+
+    /// Everything after the characters.
+    fn decode_body(
+        reader: &mut ByteReader,
+        player_1: &'static CharacterDefinition,
+        player_2: &'static CharacterDefinition,
+    ) -> Option<Self> {
+        let seed = reader.read_i32()?;
+        let rounds_to_win = read_usize(reader)?;
+        let round_seconds = match reader.read_u8()? {
+            0 => None,
+            1 => Some(read_usize(reader)?),
+            _ => return None,
+        };
+        let frames_per_second = read_usize(reader)?;
+        let settings = MatchSettings {
+            rounds_to_win,
+            round_seconds,
+            frames_per_second,
+        };
+
+        let run_count = read_usize(reader)?;
+        let runs = (0..run_count)
+            .map(|_| {
+                Some(InputRun {
+                    player_1: InputState::from_byte(reader.read_u8()?)?,
+                    player_2: InputState::from_byte(reader.read_u8()?)?,
+                    frames: reader.read_u16()?,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        reader.is_empty().then_some(Self {
+            player_1,
+            player_2,
+            seed,
+            settings,
+            runs,
+        })
+    }
+}
+
+fn write_usize(bytes: &mut Vec<u8>, value: usize) {
+    bytes.extend_from_slice(&(value as u64).to_le_bytes());
+}
+
+/// Fails for values too large for this platform, as well as for the end of
+/// the replay.
+fn read_usize(reader: &mut ByteReader) -> Option<usize> {
+    reader.read_u64()?.try_into().ok()
+}
+
+fn read_character(reader: &mut ByteReader) -> Result<&'static CharacterDefinition, ReplayError> {
+    let len = read_usize(reader).ok_or(ReplayError::Corrupt)?;
+    let name = reader
+        .read_bytes(len)
+        .and_then(|name| std::str::from_utf8(name).ok())
+        .ok_or(ReplayError::Corrupt)?;
+
+    find_character(name).ok_or_else(|| ReplayError::UnknownCharacter(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::DEFAULT_CHARACTER,
+        gameplay::{DirectionInput, InputState, MatchSettings},
+    };
+
+    use super::{REPLAY_VERSION, Replay, ReplayError};
+
+    fn replay() -> Replay {
+        let settings = MatchSettings {
+            round_seconds: None,
+            ..MatchSettings::default()
+        };
+        let mut replay = Replay::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, -5, settings);
+
+        let forward = InputState {
+            direction: DirectionInput::from_notation(&'6'),
+            ..InputState::default()
+        };
+        for frame in 0..100_000 {
+            let player_1 = if frame % 1000 < 10 {
+                forward
+            } else {
+                InputState::default()
+            };
+            replay.record(player_1, InputState::default());
+        }
+
+        replay
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let replay = replay();
+        let decoded = Replay::decode(&replay.encode()).unwrap();
+
+        assert_eq!(decoded.seed, -5);
+        assert_eq!(decoded.settings.round_seconds, None);
+        assert_eq!(decoded.frame_count(), 100_000);
+        assert_eq!(decoded.encode(), replay.encode());
+    }
+
+    #[test]
+    fn round_trips_settings_of_any_size() {
+        let settings = [
+            (1, Some(0), 60),
+            (1, None, 60),
+            (300, Some(70_000), 100_000),
+            (usize::MAX, Some(usize::MAX), usize::MAX),
+        ];
+
+        for (rounds_to_win, round_seconds, frames_per_second) in settings {
+            let settings = MatchSettings {
+                rounds_to_win,
+                round_seconds,
+                frames_per_second,
+            };
+            let replay = Replay::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, 0, settings);
+            let decoded = Replay::decode(&replay.encode()).unwrap();
+            assert_eq!(decoded.settings, settings);
+        }
+    }
+
+    #[test]
+    fn rejects_other_versions_and_corrupt_replays() {
+        let mut bytes = replay().encode();
+        assert_eq!(
+            Replay::decode(&bytes[..bytes.len() - 1]).err(),
+            Some(ReplayError::Corrupt)
+        );
+        assert_eq!(
+            Replay::decode(b"not a replay").err(),
+            Some(ReplayError::NotAReplay)
+        );
+
+        bytes[4..6].copy_from_slice(&(REPLAY_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            Replay::decode(&bytes).err(),
+            Some(ReplayError::UnsupportedVersion(REPLAY_VERSION + 1))
+        );
+    }
+}
@@ -0,0 +1,7 @@
+mod format;
+mod playback;
+mod script;
+
+pub use format::*;
+pub use playback::*;
+pub use script::*;
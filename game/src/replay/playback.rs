@@ -0,0 +1,169 @@
+use crate::gameplay::MatchState;
+
+use super::Replay;
+
+/// Frames played each update while fast-forwarding.
+const FAST_FORWARD_SPEED: usize = 4;
+
+/// Plays a replay by feeding its recorded inputs into a new match in place
+/// of live ones. Can be paused, stepped through a frame at a time while
+/// paused, and fast-forwarded.
+pub struct Playback {
+    replay: Replay,
+    match_state: MatchState,
+    /// The next frame to play.
+    frame: usize,
+    frame_count: usize,
+    /// Where the next frame's inputs are: the run, and the frame within it.
+    run: usize,
+    run_frame: usize,
+    paused: bool,
+    fast_forward: bool,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            match_state: MatchState::new(replay.player_1, replay.player_2, replay.settings),
+            frame: 0,
+            frame_count: replay.frame_count(),
+            run: 0,
+            run_frame: 0,
+            paused: false,
+            fast_forward: false,
+            replay,
+        }
+    }
+
+    pub fn match_state(&self) -> &MatchState {
+        &self.match_state
+    }
+
+    /// The next frame to be played.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.frame_count
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn set_fast_forward(&mut self, fast_forward: bool) {
+        self.fast_forward = fast_forward;
+    }
+
+    /// Plays a single frame while paused.
+    pub fn step(&mut self) {
+        if self.paused {
+            self.play_frame();
+        }
+    }
+
+    /// Plays the frames for a single update, unless paused.
+    pub fn update(&mut self) {
+        if self.paused {
+            return;
+        }
+
+        let frames = if self.fast_forward {
+            FAST_FORWARD_SPEED
+        } else {
+            1
+        };
+        for _ in 0..frames {
+            self.play_frame();
+        }
+    }
+
+    fn play_frame(&mut self) {
+        let Some(run) = self.replay.runs.get(self.run) else {
+            return;
+        };
+
+        self.match_state.update(run.player_1, run.player_2);
+        self.frame += 1;
+        self.run_frame += 1;
+        if self.run_frame == run.frames as usize {
+            self.run += 1;
+            self.run_frame = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::DEFAULT_CHARACTER,
+        gameplay::{
+            MatchSettings, MatchState,
+            testing::{SCRIPTED_FRAMES, scripted_inputs},
+        },
+        replay::Replay,
+    };
+
+    use super::{FAST_FORWARD_SPEED, Playback};
+
+    /// Plays a match live while recording it, returning the replay and the
+    /// checksum of the live match at the end.
+    fn record_match() -> (Replay, u64) {
+        let settings = MatchSettings::default();
+        let mut state = MatchState::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, settings);
+        let mut replay = Replay::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, 1234, settings);
+
+        for frame in 0..SCRIPTED_FRAMES {
+            let inputs = scripted_inputs(frame);
+            replay.record(inputs.0, inputs.1);
+            state.update(inputs.0, inputs.1);
+        }
+
+        (replay, state.checksum())
+    }
+
+    #[test]
+    fn playback_matches_the_recorded_match() {
+        let (replay, checksum) = record_match();
+        let replay = Replay::decode(&replay.encode()).unwrap();
+        let mut playback = Playback::new(replay);
+
+        while !playback.is_finished() {
+            playback.update();
+        }
+        assert_eq!(playback.frame(), SCRIPTED_FRAMES);
+        assert_eq!(playback.match_state().checksum(), checksum);
+    }
+
+    #[test]
+    fn pausing_stepping_and_fast_forwarding() {
+        let (replay, checksum) = record_match();
+        let mut playback = Playback::new(replay);
+
+        playback.update();
+        playback.step();
+        assert_eq!(playback.frame(), 1);
+
+        playback.toggle_pause();
+        playback.update();
+        assert_eq!(playback.frame(), 1);
+        playback.step();
+        assert_eq!(playback.frame(), 2);
+
+        playback.toggle_pause();
+        playback.set_fast_forward(true);
+        playback.update();
+        assert_eq!(playback.frame(), 2 + FAST_FORWARD_SPEED);
+
+        while !playback.is_finished() {
+            playback.update();
+        }
+        assert_eq!(playback.frame(), SCRIPTED_FRAMES);
+        assert_eq!(playback.match_state().checksum(), checksum);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    db::DEFAULT_CHARACTER,
+    gameplay::{InputState, MatchSettings},
+};
+
+use super::Replay;
+
+/// The longest a script can run for, an hour at 60 frames per second, so
+/// that a mistyped frame count can't run a match practically forever.
+pub const MAX_SCRIPT_FRAMES: usize = 60 * 60 * 60;
+
+/// A line of a script which couldn't be read, counting from 1.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ScriptError {
+    pub line: usize,
+}
+
+impl Replay {
+    /// Builds a replay of a match between two default characters from a
+    /// script written by hand. Each line holds a number of frames and then
+    /// both players' inputs for those frames, such as `10 2b 5`. Inputs are
+    /// a numpad direction relative to the way the player faces, followed by
+    /// any of the buttons a, b, c and s. Anything after a `#` is ignored.
+    pub fn from_script(script: &str) -> Result<Self, ScriptError> {
+        let mut replay = Replay::new(
+            &DEFAULT_CHARACTER,
+            &DEFAULT_CHARACTER,
+            0,
+            MatchSettings::default(),
+        );
+
+        let mut total_frames = 0;
+        for (index, line) in script.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+            let Some(frames) = words.next() else {
+                continue;
+            };
+
+            let error = || ScriptError { line: index + 1 };
+            let frames = frames
+                .parse::<usize>()
+                .ok()
+                .filter(|frames| *frames <= MAX_SCRIPT_FRAMES)
+                .ok_or_else(error)?;
+            let (Some(player_1), Some(player_2), None) = (
+                words.next().and_then(InputState::from_notation),
+                words.next().and_then(InputState::from_notation),
+                words.next(),
+            ) else {
+                return Err(error());
+            };
+
+            total_frames += frames;
+            if total_frames > MAX_SCRIPT_FRAMES {
+                return Err(error());
+            }
+            replay.record_frames(player_1, player_2, frames);
+        }
+
+        Ok(replay)
+    }
+}
@@ -0,0 +1,86 @@
+use game::{
+    db::DEFAULT_CHARACTER,
+    gameplay::{MatchResult, MatchSettings, MatchState, RoundPhase},
+    replay::{MAX_SCRIPT_FRAMES, Playback, Replay, ScriptError},
+};
+
+/// Player 1 walks up to player 2 and keeps hitting them with 5c until the
+/// match is over.
+fn knockout_script() -> String {
+    let mut script = String::from("# Walk in, then attack\n400 6 5\n");
+    for _ in 0..300 {
+        script.push_str("2 5c 5\n30 6 5\n");
+    }
+    script
+}
+
+fn play(replay: Replay) -> Playback {
+    let mut playback = Playback::new(replay);
+    while !playback.is_finished() {
+        playback.update();
+    }
+    playback
+}
+
+#[test]
+fn scripted_knockouts_win_the_match() {
+    let replay = Replay::from_script(&knockout_script()).unwrap();
+    let playback = play(replay);
+
+    let match_state = playback.match_state();
+    assert!(matches!(
+        match_state.phase,
+        RoundPhase::MatchOver(MatchResult::Player1Win)
+    ));
+    assert_eq!(match_state.player_1.win_count, 2);
+    assert_eq!(match_state.player_2.character_stats.health, 0);
+}
+
+#[test]
+fn saved_replays_play_out_like_the_live_match() {
+    let script = Replay::from_script(&knockout_script()).unwrap();
+
+    // Plays the inputs live, recording them as the game does.
+    let settings = MatchSettings::default();
+    let mut live = MatchState::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, settings);
+    let mut recording = Replay::new(&DEFAULT_CHARACTER, &DEFAULT_CHARACTER, 42, settings);
+    for (player_1, player_2) in script.inputs() {
+        recording.record(player_1, player_2);
+        live.update(player_1, player_2);
+    }
+
+    let saved = Replay::decode(&recording.encode()).unwrap();
+    assert_eq!(saved.seed, 42);
+    assert_eq!(saved.frame_count(), script.frame_count());
+    assert_eq!(play(saved).match_state().checksum(), live.checksum());
+    assert_eq!(play(script).match_state().checksum(), live.checksum());
+}
+
+#[test]
+fn script_errors_give_the_line() {
+    let script = "# Comment\n10 5 5\n\n10 6x 5\n";
+    assert_eq!(
+        Replay::from_script(script).err(),
+        Some(ScriptError { line: 4 })
+    );
+    assert_eq!(
+        Replay::from_script("five 5 5").err(),
+        Some(ScriptError { line: 1 })
+    );
+}
+
+#[test]
+fn scripts_are_limited_to_an_hour() {
+    let hour = Replay::from_script(&format!("{MAX_SCRIPT_FRAMES} 6 5")).unwrap();
+    assert_eq!(hour.frame_count(), MAX_SCRIPT_FRAMES);
+
+    assert_eq!(
+        Replay::from_script("99999999999 5 5").err(),
+        Some(ScriptError { line: 1 })
+    );
+    let script = format!("{MAX_SCRIPT_FRAMES} 6 5\n1 5 5\n");
+    assert_eq!(
+        Replay::from_script(&script).err(),
+        Some(ScriptError { line: 2 })
+    );
+}
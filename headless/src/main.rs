@@ -0,0 +1,114 @@
+//! Runs a match natively without the host, from a binary replay or a script
+//! of inputs, and prints how the match stood at the end.
+//!
+//! Usage: headless <replay or script file>
+
+use std::{env, fs, process::ExitCode};
+
+use game::{
+    gameplay::{CharacterState, MatchResult, MatchState, PlayerGameState, RoundPhase},
+    replay::{Playback, Replay, ReplayError},
+};
+
+fn main() -> ExitCode {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("Usage: headless <replay or script file>");
+        return ExitCode::FAILURE;
+    };
+
+    let replay = match fs::read(&path)
+        .map_err(|error| format!("Couldn't read {path}: {error}"))
+        .and_then(|bytes| load_replay(&bytes))
+    {
+        Ok(replay) => replay,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut playback = Playback::new(replay);
+    while !playback.is_finished() {
+        playback.update();
+    }
+
+    print_match(playback.frame(), playback.match_state());
+    ExitCode::SUCCESS
+}
+
+/// Reads the file as a binary replay, or as a script if it isn't one.
+fn load_replay(bytes: &[u8]) -> Result<Replay, String> {
+    match Replay::decode(bytes) {
+        Ok(replay) => Ok(replay),
+        Err(ReplayError::NotAReplay) => {
+            let script =
+                str::from_utf8(bytes).map_err(|_| "Not a replay or a script".to_string())?;
+            Replay::from_script(script)
+                .map_err(|error| format!("Invalid script on line {}", error.line))
+        }
+        Err(error) => Err(format!("Invalid replay: {error:?}")),
+    }
+}
+
+fn print_match(frames: usize, match_state: &MatchState) {
+    println!("frames: {frames}");
+    println!("round: {}", match_state.round);
+    println!("phase: {}", phase_name(&match_state.phase));
+    match match_state.timer {
+        Some(timer) => println!("timer: {timer}"),
+        None => println!("timer: none"),
+    }
+    println!("checksum: {:016x}", match_state.checksum());
+
+    for (number, player) in [(1, &match_state.player_1), (2, &match_state.player_2)] {
+        print_player(number, player);
+    }
+}
+
+fn print_player(number: usize, player: &PlayerGameState) {
+    println!("player {number}: {}", player.character.name);
+    println!("  health: {}", player.character_stats.health);
+    println!("  super meter: {}", player.character_stats.super_meter);
+    println!("  boost meter: {}", player.character_stats.boost_meter);
+    println!("  wins: {}", player.win_count);
+    println!("  position: {}, {}", player.pos_x, player.pos_y);
+    println!("  state: {}", state_name(&player.character_state));
+}
+
+fn phase_name(phase: &RoundPhase) -> String {
+    match phase {
+        RoundPhase::Intro(_) => "intro".to_string(),
+        RoundPhase::Fight => "fight".to_string(),
+        RoundPhase::RoundOver { result, .. } => format!("round over, {}", result_name(result)),
+        RoundPhase::MatchOver(result) => format!("match over, {}", result_name(result)),
+    }
+}
+
+fn result_name(result: &MatchResult) -> &'static str {
+    match result {
+        MatchResult::Player1Win => "player 1 wins",
+        MatchResult::Player2Win => "player 2 wins",
+        MatchResult::Draw => "draw",
+    }
+}
+
+fn state_name(state: &CharacterState) -> &'static str {
+    match state {
+        CharacterState::Idle => "idle",
+        CharacterState::Walking { .. } => "walking",
+        CharacterState::Crouching => "crouching",
+        CharacterState::Dashing { .. } => "dashing",
+        CharacterState::Jumping => "jumping",
+        CharacterState::Landing(_) => "landing",
+        CharacterState::Boosting(_) => "boosting",
+        CharacterState::Attacking { .. } => "attacking",
+        CharacterState::Throwing { .. } => "throwing",
+        CharacterState::Thrown { .. } => "thrown",
+        CharacterState::Guarding => "guarding",
+        CharacterState::Blockstun(_) => "blockstun",
+        CharacterState::Hitstun(_) => "hitstun",
+        CharacterState::KnockedDown(_) => "knocked down",
+        CharacterState::WakingUp(_) => "waking up",
+        CharacterState::Juggle { .. } => "juggled",
+    }
+}
@@ -0,0 +1,61 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+use game::replay::Replay;
+
+const SCRIPT: &str = "\
+# Walk in and attack until the match is over
+400 6 5
+2 5c 5
+30 6 5
+2 5c 5
+30 6 5
+";
+
+/// Writes a file for the test to a path unique to this test run.
+fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = env::temp_dir().join(format!("headless-{}-{name}", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Runs the headless binary on the file, returning whether it succeeded
+/// and what it printed.
+fn run(path: &PathBuf) -> (bool, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_headless"))
+        .arg(path)
+        .output()
+        .unwrap();
+    fs::remove_file(path).unwrap();
+
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn runs_scripts_and_replays_to_the_same_state() {
+    let (success, script_output, _) = run(&temp_file("script.txt", SCRIPT.as_bytes()));
+    assert!(success);
+    assert!(script_output.starts_with("frames: 464\n"));
+    assert!(script_output.contains("phase: fight\n"));
+
+    let replay = Replay::from_script(SCRIPT).unwrap().encode();
+    let (success, replay_output, _) = run(&temp_file("replay.ffrp", &replay));
+    assert!(success);
+    assert_eq!(replay_output, script_output);
+}
+
+#[test]
+fn reports_invalid_files() {
+    let (success, _, error) = run(&temp_file("invalid.txt", b"400 6 5\n2 5z 5\n"));
+    assert!(!success);
+    assert_eq!(error, "Invalid script on line 2\n");
+
+    let mut replay = Replay::from_script(SCRIPT).unwrap().encode();
+    replay.truncate(replay.len() - 1);
+    let (success, _, error) = run(&temp_file("truncated.ffrp", &replay));
+    assert!(!success);
+    assert_eq!(error, "Invalid replay: Corrupt\n");
+}